@@ -1,3 +1,5 @@
+use std::fmt;
+
 use diesel::backend::*;
 use diesel::sql_types::TypeMetadata;
 
@@ -10,6 +12,113 @@ use super::types::OciTypeMetadata;
 #[derive(Debug, Hash, PartialEq, Eq, Default)]
 pub struct Oracle;
 
+/// The version of the database server a connection is connected to
+///
+/// See [`OciConnection::server_version`](crate::OciConnection::server_version)
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct OciServerVersion {
+    major: i32,
+    minor: i32,
+    update: i32,
+    patch: i32,
+    port_update: i32,
+}
+
+impl OciServerVersion {
+    /// Create a new version from its parts
+    pub const fn new(major: i32, minor: i32, update: i32, patch: i32, port_update: i32) -> Self {
+        Self {
+            major,
+            minor,
+            update,
+            patch,
+            port_update,
+        }
+    }
+
+    /// The major release, like `19` for Oracle 19c
+    pub fn major(&self) -> i32 {
+        self.major
+    }
+
+    /// The second part of the version number
+    pub fn minor(&self) -> i32 {
+        self.minor
+    }
+
+    /// The third part of the version number (release update)
+    pub fn update(&self) -> i32 {
+        self.update
+    }
+
+    /// The fourth part of the version number
+    pub fn patch(&self) -> i32 {
+        self.patch
+    }
+
+    /// The fifth part of the version number
+    pub fn port_update(&self) -> i32 {
+        self.port_update
+    }
+}
+
+impl From<&oracle::Version> for OciServerVersion {
+    fn from(version: &oracle::Version) -> Self {
+        Self::new(
+            version.major(),
+            version.minor(),
+            version.update(),
+            version.patch(),
+            version.port_update(),
+        )
+    }
+}
+
+impl fmt::Display for OciServerVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}.{}",
+            self.major, self.minor, self.update, self.patch, self.port_update
+        )
+    }
+}
+
+/// How `LIMIT` and `OFFSET` clauses are written
+///
+/// See [`OciConnection::set_pagination_mode`](crate::OciConnection::set_pagination_mode)
+#[derive(Debug, Hash, PartialEq, Eq, Default, Clone, Copy)]
+#[non_exhaustive]
+pub enum OciPaginationMode {
+    /// Use the row limiting clause (`OFFSET n ROWS FETCH NEXT m ROWS ONLY`)
+    /// introduced with Oracle 12c
+    #[default]
+    OffsetFetch,
+    /// Wrap paginated queries into subqueries filtering on `ROWNUM`
+    ///
+    /// This works with Oracle releases before 12c. The wrapping
+    /// query selects all columns of the original query, so that
+    /// query must not contain multiple columns with the same name.
+    ///
+    /// **Queries with an offset return an additional `RNUM` column after
+    /// the selected columns.** Queries built with diesels query builder
+    /// read their columns by position and ignore it, but it is part of the
+    /// result of dynamic selects (`diesel-dynamic-schema`) and counts
+    /// towards the number of fields of each row.
+    RowNum,
+}
+
+impl OciPaginationMode {
+    /// The mode supported by the given server version
+    pub(crate) fn for_server(version: OciServerVersion) -> Self {
+        if version.major < 12 {
+            OciPaginationMode::RowNum
+        } else {
+            OciPaginationMode::OffsetFetch
+        }
+    }
+}
+
 impl Backend for Oracle {
     type QueryBuilder = OciQueryBuilder;
     type BindCollector<'a> = OracleBindCollector<'a>;
@@ -27,18 +136,18 @@ impl DieselReserveSpecialization for Oracle {}
 impl SqlDialect for Oracle {
     type ReturningClause = OracleReturningClause;
 
-    type OnConflictClause = sql_dialect::on_conflict_clause::DoesNotSupportOnConflictClause;
+    type OnConflictClause = OracleMergeOnConflictClause;
 
     type InsertWithDefaultKeyword = sql_dialect::default_keyword_for_insert::IsoSqlDefaultKeyword;
     type BatchInsertSupport = OracleStyleBatchInsert;
     type DefaultValueClauseForInsert = sql_dialect::default_value_clause::AnsiDefaultValueClause;
 
     type EmptyFromClauseSyntax = OracleDualForEmptySelectClause;
-    type ExistsSyntax = OracleExistsSyntax;
+    type ExistsSyntax = sql_dialect::exists_syntax::AnsiSqlExistsSyntax;
 
     type ConcatClause = sql_dialect::concat_clause::ConcatWithPipesClause;
     type ArrayComparison = sql_dialect::array_comparison::AnsiSqlArrayComparison;
-    type SelectStatementSyntax = sql_dialect::select_statement_syntax::AnsiSqlSelectStatement;
+    type SelectStatementSyntax = OracleSelectStatementSyntax;
     type AliasSyntax = OracleAliasSyntax;
 
     type WindowFrameClauseGroupSupport =
@@ -57,5 +166,16 @@ impl SqlDialect for Oracle {
 pub struct OracleStyleBatchInsert;
 pub struct OracleReturningClause;
 pub struct OracleDualForEmptySelectClause;
-pub struct OracleExistsSyntax;
 pub struct OracleAliasSyntax;
+pub struct OracleSelectStatementSyntax;
+
+/// Oracle has no `ON CONFLICT` clause, upserts are written as `MERGE INTO`
+/// statements by the [`OciQueryBuilder`] instead
+pub struct OracleMergeOnConflictClause;
+
+impl sql_dialect::on_conflict_clause::SupportsOnConflictClause for OracleMergeOnConflictClause {}
+impl sql_dialect::on_conflict_clause::SupportsOnConflictClauseWhere
+    for OracleMergeOnConflictClause
+{
+}
+impl sql_dialect::on_conflict_clause::PgLikeOnConflictClause for OracleMergeOnConflictClause {}
@@ -38,8 +38,9 @@ fn default_value(d: &'_ OciDataType) -> &'static dyn oracle::sql_type::ToSql {
         OciDataType::Text => &oracle::sql_type::OracleType::Varchar2(0),
         OciDataType::Binary => &oracle::sql_type::OracleType::BLOB,
         OciDataType::Date => &oracle::sql_type::OracleType::Date,
-        OciDataType::Time => unimplemented!("No time support in the oracle crate yet"),
+        OciDataType::Time => &oracle::sql_type::OracleType::IntervalDS(0, 9),
         OciDataType::Timestamp => &oracle::sql_type::OracleType::Timestamp(0),
+        OciDataType::TimestampTz => &oracle::sql_type::OracleType::TimestampTZ(9),
         OciDataType::IntervalYM => &oracle::sql_type::OracleType::IntervalYM(2),
         OciDataType::IntervalDS => &oracle::sql_type::OracleType::IntervalDS(2, 6),
     }
@@ -63,7 +64,8 @@ impl<'a> BindCollector<'a, Oracle> for OracleBindCollector<'a> {
             let out = BindValue::NotSet(ty);
             let mut out = diesel::serialize::Output::<Oracle>::new(out, metadata_lookup);
 
-            bind.to_sql(&mut out).unwrap();
+            bind.to_sql(&mut out)
+                .map_err(diesel::result::Error::SerializationError)?;
             out.into_inner()
         };
         let len = self.binds.len();
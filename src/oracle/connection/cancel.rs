@@ -0,0 +1,97 @@
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use diesel::result::QueryResult;
+
+use super::ErrorHelper;
+
+/// A handle to cancel the statement currently executed by a [`OciConnection`]
+///
+/// The handle can be sent to other threads. Cancelling a statement
+/// returns an error for that statement, which can be detected via
+/// [`OciErrorExt::is_cancelled`]. Open transactions are not affected
+/// by cancelling a statement.
+///
+/// ```no_run
+/// # use diesel_oci::OciConnection;
+/// # use diesel::prelude::*;
+/// # fn run(conn: &mut OciConnection) {
+/// let handle = conn.cancel_handle();
+/// std::thread::spawn(move || {
+///     std::thread::sleep(std::time::Duration::from_secs(5));
+///     handle.cancel().unwrap();
+/// });
+/// let res = diesel::sql_query("SELECT COUNT(*) FROM all_objects, all_objects").execute(conn);
+/// # }
+/// ```
+///
+/// [`OciConnection`]: super::OciConnection
+/// [`OciErrorExt::is_cancelled`]: super::OciErrorExt::is_cancelled
+#[derive(Debug, Clone)]
+pub struct OciCancelHandle {
+    state: Arc<CancelState>,
+}
+
+impl OciCancelHandle {
+    pub(super) fn new(state: Arc<CancelState>) -> Self {
+        Self { state }
+    }
+
+    /// Cancel the currently executed statement
+    ///
+    /// Does nothing if the connection is not executing a statement
+    pub fn cancel(&self) -> QueryResult<()> {
+        let running = self.state.lock();
+        if let Some(conn) = *running {
+            // SAFETY: the pointer is only set while the connection is borrowed
+            // by a `RunningStatement` and cleared before that borrow ends.
+            // We hold the lock, so the pointer cannot be cleared in the meantime.
+            // `oracle::Connection` is `Sync`, so calling `break_execution` from
+            // another thread is fine
+            let conn = unsafe { conn.0.as_ref() };
+            conn.break_execution().map_err(ErrorHelper::from)?;
+        }
+        Ok(())
+    }
+}
+
+/// Tracks the connection that is currently executing a statement
+#[derive(Debug, Default)]
+pub(super) struct CancelState {
+    running: Mutex<Option<ConnPtr>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ConnPtr(NonNull<oracle::Connection>);
+
+// SAFETY: `oracle::Connection` is `Send` and `Sync`, the pointer
+// is only dereferenced while the connection is borrowed
+unsafe impl Send for ConnPtr {}
+unsafe impl Sync for ConnPtr {}
+
+impl CancelState {
+    fn lock(&self) -> MutexGuard<'_, Option<ConnPtr>> {
+        self.running.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Marks `conn` as executing a statement until the returned guard is dropped
+    pub(super) fn register<'a>(&'a self, conn: &'a oracle::Connection) -> RunningStatement<'a> {
+        *self.lock() = Some(ConnPtr(NonNull::from(conn)));
+        RunningStatement {
+            state: self,
+            _conn: PhantomData,
+        }
+    }
+}
+
+pub(super) struct RunningStatement<'a> {
+    state: &'a CancelState,
+    _conn: PhantomData<&'a oracle::Connection>,
+}
+
+impl Drop for RunningStatement<'_> {
+    fn drop(&mut self) {
+        *self.state.lock() = None;
+    }
+}
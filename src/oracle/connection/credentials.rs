@@ -0,0 +1,115 @@
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+type CredentialCallback =
+    dyn Fn() -> Result<OciCredentials, Box<dyn Error + Send + Sync>> + Send + Sync;
+
+/// Credentials returned by a [`OciCredentialProvider`]
+#[derive(Clone)]
+pub struct OciCredentials {
+    /// The username to use, `None` keeps the configured username
+    pub username: Option<String>,
+    /// The password to use
+    pub password: String,
+}
+
+impl OciCredentials {
+    /// Credentials only providing a password
+    pub fn password(password: impl Into<String>) -> Self {
+        Self {
+            username: None,
+            password: password.into(),
+        }
+    }
+}
+
+impl std::fmt::Debug for OciCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OciCredentials")
+            .field("username", &self.username)
+            .field("password", &"***")
+            .finish()
+    }
+}
+
+/// Provides credentials each time a new connection is established
+///
+/// This allows connection pools to pick up rotated passwords
+/// without changing the database url or connection options.
+///
+/// ```no_run
+/// # use diesel_oci::{OciConnectOptions, OciCredentialProvider, OciCredentials};
+/// let options = OciConnectOptions::new("user", "", "127.0.0.1/MY_DB")
+///     .credential_provider(OciCredentialProvider::password_from_env("DB_PASSWORD"));
+///
+/// let options = OciConnectOptions::new("user", "", "127.0.0.1/MY_DB")
+///     .credential_provider(OciCredentialProvider::new(|| {
+///         Ok(OciCredentials::password("secret"))
+///     }));
+/// ```
+#[derive(Clone)]
+pub struct OciCredentialProvider {
+    description: String,
+    callback: Arc<CredentialCallback>,
+}
+
+impl OciCredentialProvider {
+    /// Use a custom callback that is called on each connect
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: Fn() -> Result<OciCredentials, Box<dyn Error + Send + Sync>> + Send + Sync + 'static,
+    {
+        Self {
+            description: String::from("callback"),
+            callback: Arc::new(callback),
+        }
+    }
+
+    /// Read the password from the given environment variable on each connect
+    pub fn password_from_env(variable: impl Into<String>) -> Self {
+        let variable = variable.into();
+        Self {
+            description: format!("environment variable `{}`", variable),
+            callback: Arc::new(move || {
+                let password = std::env::var(&variable).map_err(|e| {
+                    format!("Could not read environment variable `{}`: {}", variable, e)
+                })?;
+                Ok(OciCredentials::password(password))
+            }),
+        }
+    }
+
+    /// Read the password from the given file on each connect
+    ///
+    /// A trailing newline is not considered to be part of the password
+    pub fn password_from_file(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self {
+            description: format!("file `{}`", path.display()),
+            callback: Arc::new(move || {
+                let password = std::fs::read_to_string(&path).map_err(|e| {
+                    format!("Could not read password file `{}`: {}", path.display(), e)
+                })?;
+                let password = password
+                    .strip_suffix('\n')
+                    .map(|p| p.strip_suffix('\r').unwrap_or(p))
+                    .unwrap_or(&password);
+                Ok(OciCredentials::password(password))
+            }),
+        }
+    }
+
+    /// Obtain the current credentials from this provider
+    pub fn credentials(&self) -> Result<OciCredentials, Box<dyn Error + Send + Sync>> {
+        (self.callback)()
+    }
+}
+
+impl std::fmt::Debug for OciCredentialProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("OciCredentialProvider")
+            .field(&self.description)
+            .finish()
+    }
+}
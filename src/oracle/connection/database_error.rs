@@ -0,0 +1,208 @@
+use diesel::result::{DatabaseErrorInformation, DatabaseErrorKind};
+
+/// Detailed information about an error reported by the Oracle database
+///
+/// Instances of this type are returned as part of
+/// [`diesel::result::Error::DatabaseError`]. Use [`OciErrorExt`] to
+/// access the underlying `ORA-` error code.
+#[derive(Debug, Clone)]
+pub struct OciDatabaseError {
+    code: i32,
+    message: String,
+    offset: u32,
+    constraint_name: Option<String>,
+    table_name: Option<String>,
+    column_name: Option<String>,
+}
+
+impl OciDatabaseError {
+    pub(crate) fn new(code: i32, offset: u32, message: &str) -> Self {
+        // Oracle usually includes the error code into the message,
+        // but we cannot rely on that for all error sources
+        let message = if message.starts_with("ORA-") || message.starts_with("DPI-") {
+            message.to_owned()
+        } else {
+            format!("ORA-{:05}: {}", code, message)
+        };
+        let (constraint_name, table_name, column_name) = parse_error_details(code, &message);
+        Self {
+            code,
+            message,
+            offset,
+            constraint_name,
+            table_name,
+            column_name,
+        }
+    }
+
+    pub(crate) fn from_db_error(e: &oracle::DbError) -> Self {
+        Self::new(e.code(), e.offset(), e.message())
+    }
+
+    /// The `ORA-` error code reported by the database
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// The diesel error kind corresponding to the `ORA-` error code
+    pub fn kind(&self) -> DatabaseErrorKind {
+        error_kind(self.code)
+    }
+}
+
+impl DatabaseErrorInformation for OciDatabaseError {
+    fn message(&self) -> &str {
+        &self.message
+    }
+
+    fn details(&self) -> Option<&str> {
+        None
+    }
+
+    fn hint(&self) -> Option<&str> {
+        None
+    }
+
+    fn table_name(&self) -> Option<&str> {
+        self.table_name.as_deref()
+    }
+
+    fn column_name(&self) -> Option<&str> {
+        self.column_name.as_deref()
+    }
+
+    fn constraint_name(&self) -> Option<&str> {
+        self.constraint_name.as_deref()
+    }
+
+    fn statement_position(&self) -> Option<i32> {
+        if self.offset == 0 {
+            None
+        } else {
+            i32::try_from(self.offset).ok()
+        }
+    }
+}
+
+/// Oracle specific extensions for [`diesel::result::Error`]
+pub trait OciErrorExt {
+    /// Returns the `ORA-` error code if the error was reported by the database
+    ///
+    /// For example this returns `Some(1)` for
+    /// `ORA-00001: unique constraint (…) violated`
+    fn ora_code(&self) -> Option<i32>;
+
+    /// Returns `true` if the statement was cancelled via a [`OciCancelHandle`]
+    ///
+    /// [`OciCancelHandle`]: super::OciCancelHandle
+    fn is_cancelled(&self) -> bool;
+
+    /// Returns `true` if the statement exceeded the configured call timeout
+    fn is_timeout(&self) -> bool;
+}
+
+impl OciErrorExt for diesel::result::Error {
+    fn ora_code(&self) -> Option<i32> {
+        match self {
+            diesel::result::Error::DatabaseError(_, info) => parse_ora_code(info.message()),
+            _ => None,
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        // ORA-01013: user requested cancel of current operation
+        self.ora_code() == Some(1013)
+    }
+
+    fn is_timeout(&self) -> bool {
+        match self {
+            // DPI-1067: call timeout exceeded
+            diesel::result::Error::DatabaseError(_, info) => info.message().starts_with("DPI-1067"),
+            _ => false,
+        }
+    }
+}
+
+fn parse_ora_code(message: &str) -> Option<i32> {
+    let code = message.strip_prefix("ORA-")?;
+    let end = code
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(code.len());
+    code[..end].parse().ok()
+}
+
+// https://docs.oracle.com/en/database/oracle/oracle-database/19/errmg/
+pub(crate) fn error_kind(code: i32) -> DatabaseErrorKind {
+    match code {
+        // unique constraint violated
+        1 => DatabaseErrorKind::UniqueViolation,
+        // integrity constraint violated - parent key not found / child record found
+        2291 | 2292 => DatabaseErrorKind::ForeignKeyViolation,
+        // cannot insert NULL / cannot update to NULL
+        1400 | 1407 => DatabaseErrorKind::NotNullViolation,
+        // check constraint violated
+        2290 => DatabaseErrorKind::CheckViolation,
+        // can't serialize access for this transaction
+        8177 => DatabaseErrorKind::SerializationFailure,
+        // may not perform insert/delete/update operation inside a READ ONLY transaction
+        1456 => DatabaseErrorKind::ReadOnlyTransaction,
+        // session killed, not logged on, end-of-file on communication channel,
+        // not connected to ORACLE, connection lost contact
+        28 | 1012 | 3113 | 3114 | 3135 => DatabaseErrorKind::ClosedConnection,
+        _ => DatabaseErrorKind::Unknown,
+    }
+}
+
+type ErrorDetails = (Option<String>, Option<String>, Option<String>);
+
+// Extracts constraint, table and column names from messages like
+//
+// * `ORA-00001: unique constraint (SCHEMA.CONSTRAINT) violated`
+// * `ORA-00001: unique constraint (SCHEMA.CONSTRAINT) violated on table SCHEMA.TABLE columns (COL)`
+// * `ORA-02291: integrity constraint (SCHEMA.CONSTRAINT) violated - parent key not found`
+// * `ORA-01400: cannot insert NULL into ("SCHEMA"."TABLE"."COLUMN")`
+// * `ORA-01407: cannot update ("SCHEMA"."TABLE"."COLUMN") to NULL`
+fn parse_error_details(code: i32, message: &str) -> ErrorDetails {
+    match code {
+        1 | 2290 | 2291 | 2292 => {
+            let constraint_name = message
+                .split_once("constraint (")
+                .and_then(|(_, rest)| rest.split_once(')'))
+                .map(|(name, _)| unqualified(name));
+            let table_name = message
+                .split_once(" on table ")
+                .map(|(_, rest)| rest.split_whitespace().next().unwrap_or_default())
+                .map(unqualified);
+            let column_name = message
+                .split_once(" columns (")
+                .and_then(|(_, rest)| rest.split_once(')'))
+                .map(|(name, _)| name.trim().to_owned());
+            (constraint_name, table_name, column_name)
+        }
+        1400 | 1407 => {
+            let qualified_column = message
+                .split_once('(')
+                .and_then(|(_, rest)| rest.split_once(')'))
+                .map(|(name, _)| {
+                    name.split('.')
+                        .map(|part| part.trim_matches('"').to_owned())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            match qualified_column.as_slice() {
+                [.., table, column] => (None, Some(table.clone()), Some(column.clone())),
+                [column] => (None, None, Some(column.clone())),
+                [] => (None, None, None),
+            }
+        }
+        _ => (None, None, None),
+    }
+}
+
+fn unqualified(name: &str) -> String {
+    name.rsplit('.')
+        .next()
+        .unwrap_or(name)
+        .trim_matches('"')
+        .to_owned()
+}
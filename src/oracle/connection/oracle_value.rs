@@ -1,4 +1,5 @@
 use crate::oracle::types::OciDataType;
+use diesel::result::{Error, QueryResult};
 
 /// A unserialized value as received from the database
 #[derive(Debug, Clone)]
@@ -23,6 +24,10 @@ pub(crate) enum InnerValue<'a> {
     Date(chrono_time::NaiveDate),
     #[cfg(feature = "chrono")]
     Timestamp(chrono_time::NaiveDateTime),
+    #[cfg(feature = "chrono")]
+    Time(chrono_time::NaiveTime),
+    #[cfg(feature = "chrono")]
+    TimestampTz(chrono_time::DateTime<chrono_time::FixedOffset>),
 }
 
 impl<'a> OracleValue<'a> {
@@ -33,10 +38,28 @@ impl<'a> OracleValue<'a> {
     }
 
     /// Get the datatype of the underlying value
+    ///
+    /// # Panics
+    ///
+    /// Panics for database side types that cannot be mapped
+    /// to any [`OciDataType`], use [`OracleValue::try_value_type`]
+    /// to handle them
+    #[deprecated(note = "use `OracleValue::try_value_type` instead")]
     pub fn value_type(&self) -> OciDataType {
+        match self.try_value_type() {
+            Ok(tpe) => tpe,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Get the datatype of the underlying value
+    ///
+    /// Returns an error for database side types that
+    /// cannot be mapped to any [`OciDataType`]
+    pub fn try_value_type(&self) -> QueryResult<OciDataType> {
         use self::InnerValue::*;
 
-        match self.inner {
+        let tpe = match self.inner {
             SmallInt(_) => OciDataType::SmallInt,
             Integer(_) => OciDataType::Integer,
             BigInt(_) => OciDataType::BigInt,
@@ -48,6 +71,10 @@ impl<'a> OracleValue<'a> {
             Date(_) => OciDataType::Date,
             #[cfg(feature = "chrono")]
             Timestamp(_) => OciDataType::Timestamp,
+            #[cfg(feature = "chrono")]
+            Time(_) => OciDataType::Time,
+            #[cfg(feature = "chrono")]
+            TimestampTz(_) => OciDataType::TimestampTz,
             Raw {
                 tpe: oracle::sql_type::OracleType::Varchar2(_),
                 ..
@@ -119,6 +146,14 @@ impl<'a> OracleValue<'a> {
                 tpe: oracle::sql_type::OracleType::Timestamp(_),
                 ..
             } => OciDataType::Timestamp,
+            Raw {
+                tpe: oracle::sql_type::OracleType::TimestampTZ(_),
+                ..
+            }
+            | Raw {
+                tpe: oracle::sql_type::OracleType::TimestampLTZ(_),
+                ..
+            } => OciDataType::TimestampTz,
             Raw {
                 tpe: oracle::sql_type::OracleType::CLOB,
                 ..
@@ -137,74 +172,47 @@ impl<'a> OracleValue<'a> {
             } => OciDataType::BigInt,
 
             Raw {
-                tpe: oracle::sql_type::OracleType::BFILE,
-                ..
-            }
-            | Raw {
                 tpe: oracle::sql_type::OracleType::NCLOB,
                 ..
             }
             | Raw {
-                tpe: oracle::sql_type::OracleType::RefCursor,
+                tpe: oracle::sql_type::OracleType::Long,
                 ..
             }
             | Raw {
-                tpe: oracle::sql_type::OracleType::Boolean,
+                tpe: oracle::sql_type::OracleType::Rowid,
                 ..
             }
             | Raw {
-                tpe: oracle::sql_type::OracleType::Object(_),
+                tpe: oracle::sql_type::OracleType::Xml,
                 ..
-            }
-            | Raw {
-                tpe: oracle::sql_type::OracleType::Long,
+            } => OciDataType::Text,
+            Raw {
+                tpe: oracle::sql_type::OracleType::Raw(_),
                 ..
             }
             | Raw {
                 tpe: oracle::sql_type::OracleType::LongRaw,
                 ..
-            }
-            | Raw {
-                tpe: oracle::sql_type::OracleType::TimestampTZ(_),
-                ..
-            }
-            | Raw {
-                tpe: oracle::sql_type::OracleType::TimestampLTZ(_),
+            } => OciDataType::Binary,
+            Raw {
+                tpe: oracle::sql_type::OracleType::Boolean,
                 ..
-            }
-            | Raw {
+            } => OciDataType::Bool,
+            Raw {
                 tpe: oracle::sql_type::OracleType::IntervalDS(_, _),
                 ..
-            }
-            | Raw {
+            } => OciDataType::IntervalDS,
+            Raw {
                 tpe: oracle::sql_type::OracleType::IntervalYM(_),
                 ..
+            } => OciDataType::IntervalYM,
+            Raw { ref tpe, .. } => {
+                return Err(Error::DeserializationError(
+                    format!("Unsupported oracle type: {}", tpe).into(),
+                ))
             }
-            | Raw {
-                tpe: oracle::sql_type::OracleType::Rowid,
-                ..
-            }
-            | Raw {
-                tpe: oracle::sql_type::OracleType::Raw(_),
-                ..
-            }
-            | Raw {
-                tpe: oracle::sql_type::OracleType::Json,
-                ..
-            }
-            | Raw {
-                tpe: oracle::sql_type::OracleType::Xml,
-                ..
-            } => unimplemented!(),
-            // e =>
-            // {
-            //     #[cfg(feature = "chrono")]
-            //     match e {
-            //         Date(_) => OciDataType::Date,
-            //         Timestamp(_) => OciDataType::Timestamp,
-            //         _ => unreachable!(),
-            //     }
-            // }
-        }
+        };
+        Ok(tpe)
     }
 }
@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+use diesel::connection::Connection;
+use diesel::result::{Error, QueryResult};
+
+use super::{OciConnection, OciErrorExt};
+
+/// Controls how [`OciConnection::transaction_with_retry`] retries transactions
+///
+/// By default transactions failing with `ORA-00060` (deadlock detected)
+/// or `ORA-08177` (can't serialize access) are attempted up to three times,
+/// waiting 50ms before the first retry and doubling that delay for each
+/// further retry up to one second.
+#[derive(Debug, Clone)]
+pub struct OciRetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: u32,
+    codes: Vec<i32>,
+}
+
+impl Default for OciRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(1),
+            multiplier: 2,
+            codes: vec![60, 8177],
+        }
+    }
+}
+
+impl OciRetryPolicy {
+    /// Create the default retry policy
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of attempts including the first one
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// The delay before the first retry
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// The upper bound for the delay between two attempts
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// The factor the delay is multiplied with after each retry
+    pub fn multiplier(mut self, multiplier: u32) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Additionally retry transactions failing with the given `ORA-` error code
+    pub fn retry_on(mut self, ora_code: i32) -> Self {
+        self.codes.push(ora_code);
+        self
+    }
+
+    /// Whether a transaction failing with `error` should be retried
+    pub fn is_retryable(&self, error: &Error) -> bool {
+        error
+            .ora_code()
+            .is_some_and(|code| self.codes.contains(&code))
+    }
+
+    fn backoff(&self, retry: u32) -> Duration {
+        let factor = self.multiplier.saturating_pow(retry);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+impl OciConnection {
+    /// Run `f` in a transaction and retry it on deadlocks and serialization failures
+    ///
+    /// The transaction is only retried if it was completely rolled back.
+    /// This is not the case if this function is called inside of another
+    /// transaction or if a DDL statement implicitly committed parts of it,
+    /// the error is returned as is then. See [`OciRetryPolicy`] for the
+    /// errors that are retried by default.
+    ///
+    /// ```no_run
+    /// # use diesel_oci::{OciConnection, OciRetryPolicy};
+    /// # use diesel::prelude::*;
+    /// # fn run(conn: &mut OciConnection) -> QueryResult<()> {
+    /// conn.transaction_with_retry(OciRetryPolicy::new().max_attempts(5), |conn| {
+    ///     diesel::sql_query("UPDATE accounts SET balance = balance - 10 WHERE id = 1")
+    ///         .execute(conn)?;
+    ///     diesel::sql_query("UPDATE accounts SET balance = balance + 10 WHERE id = 2")
+    ///         .execute(conn)
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn transaction_with_retry<T, F>(
+        &mut self,
+        policy: OciRetryPolicy,
+        mut f: F,
+    ) -> QueryResult<T>
+    where
+        F: FnMut(&mut Self) -> QueryResult<T>,
+    {
+        let nested = self
+            .transaction_manager
+            .status
+            .transaction_depth()?
+            .is_some();
+        let mut attempt = 1;
+        loop {
+            let mut implicitly_committed = false;
+            let res = self.transaction(|conn| {
+                let res = f(conn);
+                implicitly_committed = conn.transaction_manager.implicit_commit_depth.is_some();
+                res
+            });
+            match res {
+                Err(e)
+                    if attempt < policy.max_attempts
+                        && !nested
+                        && !implicitly_committed
+                        && policy.is_retryable(&e)
+                        && matches!(
+                            self.transaction_manager.status.transaction_depth(),
+                            Ok(None)
+                        ) =>
+                {
+                    std::thread::sleep(policy.backoff(attempt - 1));
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+}
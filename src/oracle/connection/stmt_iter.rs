@@ -1,6 +1,10 @@
+use std::rc::Rc;
+
 use diesel::QueryResult;
 
+use super::cancel::RunningStatement;
 use super::row::OciRow;
+use super::ErrorHelper;
 
 pub struct RowIter {
     rows: Vec<OciRow>,
@@ -20,3 +24,62 @@ impl Iterator for RowIter {
         self.rows.pop().map(Ok)
     }
 }
+
+/// Fetches rows from the database while iterating
+pub struct RowByRowIter<'conn> {
+    inner: RowByRowInner<'conn>,
+}
+
+enum RowByRowInner<'conn> {
+    Streaming {
+        result_set: oracle::ResultSet<'static, oracle::Row>,
+        column_infos: Rc<Vec<oracle::ColumnInfo>>,
+        running: Option<RunningStatement<'conn>>,
+    },
+    // statements with a returning clause return all rows at once
+    Buffered(RowIter),
+}
+
+impl<'conn> RowByRowIter<'conn> {
+    pub(super) fn streaming(result_set: oracle::ResultSet<'static, oracle::Row>) -> Self {
+        let column_infos = Rc::new(result_set.column_info().to_owned());
+        Self {
+            inner: RowByRowInner::Streaming {
+                result_set,
+                column_infos,
+                running: None,
+            },
+        }
+    }
+
+    pub(super) fn buffered(rows: RowIter) -> Self {
+        Self {
+            inner: RowByRowInner::Buffered(rows),
+        }
+    }
+
+    /// Allows to cancel fetching further rows
+    pub(super) fn register_cancel(&mut self, register: impl FnOnce() -> RunningStatement<'conn>) {
+        if let RowByRowInner::Streaming { running, .. } = &mut self.inner {
+            *running = Some(register());
+        }
+    }
+}
+
+impl Iterator for RowByRowIter<'_> {
+    type Item = QueryResult<OciRow>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            RowByRowInner::Streaming {
+                result_set,
+                column_infos,
+                ..
+            } => result_set.next().map(|row| {
+                row.map(|row| OciRow::new(row, column_infos.clone()))
+                    .map_err(|e| ErrorHelper::from(e).into())
+            }),
+            RowByRowInner::Buffered(rows) => rows.next(),
+        }
+    }
+}
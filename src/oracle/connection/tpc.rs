@@ -0,0 +1,359 @@
+use std::num::NonZeroU32;
+
+use diesel::connection::{Instrumentation, InstrumentationEvent, TransactionDepthChange};
+use diesel::result::{Error, QueryResult};
+use oracle::sql_type::{OracleType, ToSql};
+
+use super::transaction::OCITransactionManager;
+use super::{ErrorHelper, OciConnection, OciDatabaseError};
+
+const XA_OK: i64 = 0;
+const XA_RDONLY: i64 = 3;
+const MAX_XID_PART_LENGTH: usize = 64;
+
+/// The identifier of a branch of a distributed (XA) transaction
+///
+/// See [`OciConnection::tpc_begin`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OciXid {
+    format_id: i32,
+    global_transaction_id: Vec<u8>,
+    branch_qualifier: Vec<u8>,
+}
+
+impl OciXid {
+    /// Create a new transaction identifier
+    ///
+    /// The global transaction id and the branch qualifier
+    /// must not be longer than 64 bytes
+    pub fn new(
+        format_id: i32,
+        global_transaction_id: impl Into<Vec<u8>>,
+        branch_qualifier: impl Into<Vec<u8>>,
+    ) -> Self {
+        Self {
+            format_id,
+            global_transaction_id: global_transaction_id.into(),
+            branch_qualifier: branch_qualifier.into(),
+        }
+    }
+
+    /// The format identifier
+    pub fn format_id(&self) -> i32 {
+        self.format_id
+    }
+
+    /// The global transaction id shared by all branches of the transaction
+    pub fn global_transaction_id(&self) -> &[u8] {
+        &self.global_transaction_id
+    }
+
+    /// The branch qualifier identifying this branch of the transaction
+    pub fn branch_qualifier(&self) -> &[u8] {
+        &self.branch_qualifier
+    }
+
+    fn validate(&self) -> QueryResult<()> {
+        if self.global_transaction_id.len() > MAX_XID_PART_LENGTH
+            || self.branch_qualifier.len() > MAX_XID_PART_LENGTH
+        {
+            return Err(Error::QueryBuilderError(
+                format!(
+                    "The global transaction id and the branch qualifier \
+                     of a xid must not be longer than {} bytes",
+                    MAX_XID_PART_LENGTH
+                )
+                .into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Two-phase commit support
+///
+/// These functions use the `DBMS_XA` PL/SQL package. The oracle client library
+/// has a native two-phase commit API, but the `oracle` crate does not expose it.
+/// The database user requires the following privileges:
+///
+/// * `EXECUTE` on `DBMS_XA`, which is granted to `PUBLIC` by default
+/// * `FORCE TRANSACTION` to commit or roll back prepared transactions of the
+///   user from another session, e.g. after [`OciConnection::tpc_recover`].
+///   Transactions of other users require `FORCE ANY TRANSACTION`.
+/// * `SELECT` on `DBA_PENDING_TRANSACTIONS` to use [`OciConnection::tpc_recover`]
+///
+/// ```no_run
+/// # use diesel_oci::{OciConnection, OciXid};
+/// # use diesel::prelude::*;
+/// # fn run(conn: &mut OciConnection) -> QueryResult<()> {
+/// let xid = OciXid::new(1, "order-4711", "inventory");
+/// conn.tpc_begin(&xid)?;
+/// diesel::sql_query("UPDATE stock SET amount = amount - 1 WHERE id = 42").execute(conn)?;
+/// if conn.tpc_prepare()? {
+///     // once all other participants are prepared as well
+///     conn.tpc_commit(&xid)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+impl OciConnection {
+    /// Start a new branch of a distributed transaction
+    ///
+    /// Statements executed afterwards are part of this branch until it is
+    /// prepared via [`OciConnection::tpc_prepare`], or committed or rolled
+    /// back directly. Savepoints created via [`diesel::Connection::transaction`]
+    /// are nested into the branch. Returns an error if a transaction is already open.
+    pub fn tpc_begin(&mut self, xid: &OciXid) -> QueryResult<()> {
+        xid.validate()?;
+        if self
+            .transaction_manager
+            .status
+            .transaction_depth()?
+            .is_some()
+        {
+            return Err(Error::AlreadyInTransaction);
+        }
+        self.instrumentation
+            .on_connection_event(InstrumentationEvent::begin_transaction(outermost()));
+        self.raw.set_autocommit(false);
+        if let Err(e) = self.xa_call(
+            "BEGIN :rc := DBMS_XA.XA_START(DBMS_XA_XID(:format_id, :gtrid, :bqual), \
+             DBMS_XA.TMNOFLAGS); :oer := DBMS_XA.XA_GETLASTOER(); END;",
+            xid,
+        ) {
+            self.raw.set_autocommit(self.autocommit);
+            return Err(e);
+        }
+        self.transaction_manager.xid = Some(xid.clone());
+        OCITransactionManager::change_transaction_depth(self, TransactionDepthChange::IncreaseDepth)
+    }
+
+    /// Prepare the branch started via [`OciConnection::tpc_begin`] for commit
+    ///
+    /// Afterwards the connection is no longer part of the distributed transaction.
+    /// The prepared branch needs to be committed or rolled back via
+    /// [`OciConnection::tpc_commit`] or [`OciConnection::tpc_rollback`], which
+    /// may happen from any connection. Returns `false` if the branch did not
+    /// modify any data, in which case it is already completed.
+    pub fn tpc_prepare(&mut self) -> QueryResult<bool> {
+        let xid = self.active_xid()?;
+        self.xa_end(&xid)?;
+        let rc = self.xa_call(
+            "BEGIN :rc := DBMS_XA.XA_PREPARE(DBMS_XA_XID(:format_id, :gtrid, :bqual)); \
+             :oer := DBMS_XA.XA_GETLASTOER(); END;",
+            &xid,
+        );
+        self.instrumentation
+            .on_connection_event(InstrumentationEvent::commit_transaction(outermost()));
+        self.leave_branch()?;
+        // the outcome is decided by the coordinator
+        self.transaction_manager.discard_callbacks();
+        Ok(rc? == XA_OK)
+    }
+
+    /// Commit a branch of a distributed transaction
+    ///
+    /// If `xid` is the branch started on this connection and not yet
+    /// prepared, it is committed using a one-phase commit.
+    pub fn tpc_commit(&mut self, xid: &OciXid) -> QueryResult<()> {
+        xid.validate()?;
+        if self.transaction_manager.xid.as_ref() == Some(xid) {
+            self.active_xid()?;
+            self.xa_end(xid)?;
+            let res = self.xa_call(
+                "BEGIN :rc := DBMS_XA.XA_COMMIT(DBMS_XA_XID(:format_id, :gtrid, :bqual), TRUE); \
+                 :oer := DBMS_XA.XA_GETLASTOER(); END;",
+                xid,
+            );
+            self.instrumentation
+                .on_connection_event(InstrumentationEvent::commit_transaction(outermost()));
+            self.leave_branch()?;
+            self.transaction_manager.transaction_finished(res.is_ok());
+            res.map(|_| ())
+        } else {
+            self.xa_call(
+                "BEGIN :rc := DBMS_XA.XA_COMMIT(DBMS_XA_XID(:format_id, :gtrid, :bqual), FALSE); \
+                 :oer := DBMS_XA.XA_GETLASTOER(); END;",
+                xid,
+            )
+            .map(|_| ())
+        }
+    }
+
+    /// Roll back a branch of a distributed transaction
+    ///
+    /// This works for the branch started on this connection as
+    /// well as for prepared branches.
+    pub fn tpc_rollback(&mut self, xid: &OciXid) -> QueryResult<()> {
+        xid.validate()?;
+        if self.transaction_manager.xid.as_ref() == Some(xid) {
+            self.active_xid()?;
+            self.instrumentation
+                .on_connection_event(InstrumentationEvent::rollback_transaction(outermost()));
+            let res = self.xa_end_and_rollback(xid);
+            self.leave_branch()?;
+            self.transaction_manager.transaction_finished(false);
+            res
+        } else {
+            self.xa_rollback(xid)
+        }
+    }
+
+    /// Forget a heuristically completed branch of a distributed transaction
+    pub fn tpc_forget(&mut self, xid: &OciXid) -> QueryResult<()> {
+        xid.validate()?;
+        self.xa_call(
+            "BEGIN :rc := DBMS_XA.XA_FORGET(DBMS_XA_XID(:format_id, :gtrid, :bqual)); \
+             :oer := DBMS_XA.XA_GETLASTOER(); END;",
+            xid,
+        )
+        .map(|_| ())
+    }
+
+    /// List all prepared branches of distributed transactions
+    ///
+    /// This allows a transaction coordinator to complete
+    /// pending branches after a failure.
+    pub fn tpc_recover(&mut self) -> QueryResult<Vec<OciXid>> {
+        let query = "SELECT x.formatid, x.gtrid, x.bqual FROM TABLE(DBMS_XA.XA_RECOVER()) x";
+        self.instrumentation
+            .on_connection_event(InstrumentationEvent::start_query(
+                &diesel::connection::StrQueryHelper::new(query),
+            ));
+        let res = self
+            .raw
+            .query_as::<(i32, Vec<u8>, Vec<u8>)>(query, &[])
+            .and_then(|rows| {
+                rows.map(|row| {
+                    row.map(|(format_id, gtrid, bqual)| OciXid::new(format_id, gtrid, bqual))
+                })
+                .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(ErrorHelper::from)
+            .map_err(Error::from);
+        self.track_connection_loss(&res);
+        self.instrumentation
+            .on_connection_event(InstrumentationEvent::finish_query(
+                &diesel::connection::StrQueryHelper::new(query),
+                res.as_ref().err(),
+            ));
+        res
+    }
+
+    /// Rolls back the branch started on this connection, if any
+    ///
+    /// Returns `true` if there was such a branch
+    pub(super) fn tpc_rollback_active(&mut self) -> QueryResult<bool> {
+        match self.transaction_manager.xid.take() {
+            Some(xid) => {
+                let res = self.xa_end_and_rollback(&xid);
+                self.raw.set_autocommit(self.autocommit);
+                res.map(|_| true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn active_xid(&self) -> QueryResult<OciXid> {
+        match (
+            &self.transaction_manager.xid,
+            self.transaction_manager.status.transaction_depth()?,
+        ) {
+            (Some(xid), Some(depth)) if depth.get() == 1 => Ok(xid.clone()),
+            (Some(_), _) => Err(Error::QueryBuilderError(
+                "Cannot complete a branch of a distributed transaction with open savepoints".into(),
+            )),
+            (None, _) => Err(Error::NotInTransaction),
+        }
+    }
+
+    fn leave_branch(&mut self) -> QueryResult<()> {
+        self.transaction_manager.xid = None;
+        self.raw.set_autocommit(self.autocommit);
+        OCITransactionManager::change_transaction_depth(self, TransactionDepthChange::DecreaseDepth)
+    }
+
+    fn xa_end(&mut self, xid: &OciXid) -> QueryResult<()> {
+        self.xa_call(
+            "BEGIN :rc := DBMS_XA.XA_END(DBMS_XA_XID(:format_id, :gtrid, :bqual), \
+             DBMS_XA.TMSUCCESS); :oer := DBMS_XA.XA_GETLASTOER(); END;",
+            xid,
+        )
+        .map(|_| ())
+    }
+
+    fn xa_rollback(&mut self, xid: &OciXid) -> QueryResult<()> {
+        self.xa_call(
+            "BEGIN :rc := DBMS_XA.XA_ROLLBACK(DBMS_XA_XID(:format_id, :gtrid, :bqual)); \
+             :oer := DBMS_XA.XA_GETLASTOER(); END;",
+            xid,
+        )
+        .map(|_| ())
+    }
+
+    fn xa_end_and_rollback(&mut self, xid: &OciXid) -> QueryResult<()> {
+        let end = self.xa_end(xid);
+        let rollback = self.xa_rollback(xid);
+        end.and(rollback)
+    }
+
+    /// Executes a `DBMS_XA` call returning its result code
+    ///
+    /// Result codes other than `XA_OK` and `XA_RDONLY` are turned into errors
+    fn xa_call(&mut self, sql: &str, xid: &OciXid) -> QueryResult<i64> {
+        self.instrumentation
+            .on_connection_event(InstrumentationEvent::start_query(
+                &diesel::connection::StrQueryHelper::new(sql),
+            ));
+        let res = self.execute_xa_call(sql, xid);
+        self.track_connection_loss(&res);
+        self.instrumentation
+            .on_connection_event(InstrumentationEvent::finish_query(
+                &diesel::connection::StrQueryHelper::new(sql),
+                res.as_ref().err(),
+            ));
+        res
+    }
+
+    fn execute_xa_call(&mut self, sql: &str, xid: &OciXid) -> QueryResult<i64> {
+        let mut stmt = self.raw.statement(sql).build().map_err(ErrorHelper::from)?;
+        let params: [&dyn ToSql; 5] = [
+            &OracleType::Int64,
+            &xid.format_id,
+            &xid.global_transaction_id,
+            &xid.branch_qualifier,
+            &OracleType::Int64,
+        ];
+        {
+            let _running = self.cancel_state.register(&self.raw);
+            stmt.execute(&params).map_err(ErrorHelper::from)?;
+        }
+        let rc = stmt.bind_value::<_, i64>(1).map_err(ErrorHelper::from)?;
+        let oer = stmt
+            .bind_value::<_, Option<i32>>(params.len())
+            .map_err(ErrorHelper::from)?
+            .unwrap_or_default();
+        match rc {
+            XA_OK | XA_RDONLY => Ok(rc),
+            _ => {
+                let message = format!("{} failed with XA error {}", xa_function(sql), rc);
+                if oer == 0 {
+                    Err(Error::QueryBuilderError(message.into()))
+                } else {
+                    let error = OciDatabaseError::new(oer, 0, &message);
+                    Err(Error::DatabaseError(error.kind(), Box::new(error)))
+                }
+            }
+        }
+    }
+}
+
+fn outermost() -> NonZeroU32 {
+    NonZeroU32::new(1).expect("1 is not 0")
+}
+
+fn xa_function(sql: &str) -> &str {
+    sql.split_once(":= ")
+        .and_then(|(_, call)| call.split_once('('))
+        .map_or("DBMS_XA", |(function, _)| function)
+}
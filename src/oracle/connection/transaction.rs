@@ -2,13 +2,23 @@ use std::num::NonZeroU32;
 
 use super::ErrorHelper;
 use super::OciConnection;
+use super::OciXid;
 use diesel::connection::Instrumentation;
 use diesel::connection::InstrumentationEvent;
 use diesel::connection::SimpleConnection;
 use diesel::connection::TransactionDepthChange;
 use diesel::connection::TransactionManager;
 use diesel::connection::TransactionManagerStatus;
-use diesel::result::QueryResult;
+use diesel::result::{Error, QueryResult};
+
+type TransactionCallback = Box<dyn FnOnce() + Send>;
+
+/// A callback registered via `OciConnection::on_commit` or `OciConnection::on_rollback`
+struct PendingCallback {
+    depth: u32,
+    on_commit: bool,
+    callback: TransactionCallback,
+}
 
 /// An implementation of `TransactionManager` which can be used for oracle
 #[allow(missing_debug_implementations)]
@@ -16,6 +26,37 @@ use diesel::result::QueryResult;
 pub struct OCITransactionManager {
     pub(super) status: TransactionManagerStatus,
     pub(super) is_test_transaction: bool,
+    /// Savepoints below this depth were released by an implicit commit
+    pub(super) implicit_commit_depth: Option<NonZeroU32>,
+    /// The distributed transaction branch started via `tpc_begin`
+    pub(super) xid: Option<OciXid>,
+    callbacks: Vec<PendingCallback>,
+}
+
+/// How DDL statements executed inside of a transaction are handled
+///
+/// Oracle implicitly commits the open transaction before and after
+/// each DDL statement, so the changes made before the statement can
+/// no longer be rolled back. Only statements starting with a DDL
+/// keyword are detected, DDL executed from PL/SQL blocks
+/// (e.g. via `EXECUTE IMMEDIATE`) is not.
+///
+/// See [`OciConnection::set_ddl_policy`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OciDdlPolicy {
+    /// Return an error without executing the statement
+    Error,
+    /// Execute the statement and report the implicit commit to the
+    /// [`Instrumentation`](diesel::connection::Instrumentation) of the
+    /// connection. This is done via an additional
+    /// `StartQuery`/`FinishQuery` event pair for the statement, which
+    /// carries a [`QueryBuilderError`](diesel::result::Error::QueryBuilderError)
+    /// describing the implicit commit, before the statement is executed
+    #[default]
+    Warn,
+    /// Execute the statement
+    Allow,
 }
 
 impl OCITransactionManager {
@@ -24,29 +65,112 @@ impl OCITransactionManager {
         OCITransactionManager::default()
     }
 
-    fn change_transaction_depth(
+    pub(super) fn change_transaction_depth(
         conn: &mut OciConnection,
         by: TransactionDepthChange,
     ) -> QueryResult<()> {
-        match Self::transaction_manager_status_mut(conn) {
-            TransactionManagerStatus::Valid(ref mut v) => v.change_transaction_depth(by),
+        let depth = match Self::transaction_manager_status_mut(conn) {
+            TransactionManagerStatus::Valid(ref mut v) => {
+                v.change_transaction_depth(by)?;
+                v.transaction_depth()
+            }
             TransactionManagerStatus::InError => {
-                Err(diesel::result::Error::BrokenTransactionManager)
+                return Err(diesel::result::Error::BrokenTransactionManager)
+            }
+        };
+        // savepoints created after leaving the depth of the
+        // implicit commit are valid again
+        let manager = &mut conn.transaction_manager;
+        manager.implicit_commit_depth = manager
+            .implicit_commit_depth
+            .zip(depth)
+            .map(|(committed, depth)| committed.min(depth));
+        Ok(())
+    }
+
+    /// Records that the open transaction was implicitly committed at the given depth
+    ///
+    /// All savepoints existing at that point are released by the database
+    pub(super) fn mark_implicit_commit(&mut self, depth: NonZeroU32) {
+        self.implicit_commit_depth = Some(
+            self.implicit_commit_depth
+                .map_or(depth, |committed| committed.max(depth)),
+        );
+    }
+
+    /// Registers a callback for the current transaction depth
+    ///
+    /// Returns the callback if there is no open transaction
+    pub(super) fn register_callback(
+        &mut self,
+        on_commit: bool,
+        callback: TransactionCallback,
+    ) -> Option<TransactionCallback> {
+        match self.status.transaction_depth() {
+            Ok(Some(depth)) => {
+                self.callbacks.push(PendingCallback {
+                    depth: depth.get(),
+                    on_commit,
+                    callback,
+                });
+                None
+            }
+            _ => Some(callback),
+        }
+    }
+
+    /// Hands the callbacks of a committed savepoint over to the enclosing savepoint
+    fn savepoint_committed(&mut self, depth: u32) {
+        for pending in &mut self.callbacks {
+            if pending.depth >= depth {
+                pending.depth = depth - 1;
             }
         }
     }
 
+    /// Runs the rollback callbacks of a rolled back savepoint and discards its commit callbacks
+    fn savepoint_rolled_back(&mut self, depth: u32) {
+        let (rolled_back, remaining) = std::mem::take(&mut self.callbacks)
+            .into_iter()
+            .partition::<Vec<_>, _>(|pending| pending.depth >= depth);
+        self.callbacks = remaining;
+        run_callbacks(rolled_back, false);
+    }
+
+    /// Runs the callbacks matching the outcome of the outermost transaction
+    pub(super) fn transaction_finished(&mut self, committed: bool) {
+        run_callbacks(std::mem::take(&mut self.callbacks), committed);
+    }
+
+    /// Discards all callbacks without running them
+    pub(super) fn discard_callbacks(&mut self) {
+        self.callbacks.clear();
+    }
+
+    fn savepoint_released(conn: &OciConnection, savepoint: u32) -> bool {
+        conn.transaction_manager
+            .implicit_commit_depth
+            .is_some_and(|committed| savepoint < committed.get())
+    }
+
     /// Begin a transaction with custom SQL
     ///
     /// This is used by connections to implement more complex transaction APIs
     /// to set things such as isolation levels.
     /// Returns an error if already inside of a transaction.
-    #[allow(dead_code)]
     pub fn begin_transaction_sql(conn: &mut OciConnection, sql: &str) -> QueryResult<()> {
         use diesel::result::Error::AlreadyInTransaction;
 
         if Self::get_transaction_depth(conn)?.is_none() {
-            conn.batch_execute(sql)?;
+            conn.instrumentation
+                .on_connection_event(InstrumentationEvent::begin_transaction(
+                    NonZeroU32::new(1).expect("1 is not 0"),
+                ));
+            conn.raw.set_autocommit(false);
+            if let Err(e) = conn.batch_execute(sql) {
+                conn.raw.set_autocommit(conn.autocommit);
+                return Err(e);
+            }
             Self::change_transaction_depth(conn, TransactionDepthChange::IncreaseDepth)
         } else {
             Err(AlreadyInTransaction)
@@ -82,6 +206,8 @@ impl TransactionManager<OciConnection> for OCITransactionManager {
         // DDL will never rolled back: https://asktom.oracle.com/pls/apex/f?p=100:11:0::::P11_QUESTION_ID:9532421900346923086
         // all preceding DML will be commited with a DDL statement !!!
         // c.f. https://docs.oracle.com/cd/E25054_01/server.1111/e25789/transact.htm#sthref1318
+        // If that happened the savepoints created before the DDL statement are gone,
+        // so we can only roll back the changes made after the implicit commit
         let transaction_depth = Self::get_transaction_depth(conn)?;
         let mut mark_as_broken = false;
         if let Some(depth) = transaction_depth {
@@ -89,17 +215,34 @@ impl TransactionManager<OciConnection> for OCITransactionManager {
                 .on_connection_event(InstrumentationEvent::rollback_transaction(depth));
         }
         match transaction_depth.map(|d| d.into()) {
+            Some(1) if conn.transaction_manager.xid.is_some() => {
+                let res = conn.tpc_rollback_active().map(|_| ());
+                if res.is_err() {
+                    mark_as_broken = true;
+                }
+                res
+            }
             Some(1) => {
                 let res = conn
                     .raw
                     .rollback()
                     .map_err(ErrorHelper::from)
                     .map_err(Into::into);
+                conn.track_connection_loss(&res);
                 if res.is_err() {
                     mark_as_broken = true;
                 }
 
-                conn.raw.set_autocommit(true);
+                conn.raw.set_autocommit(conn.autocommit);
+                res
+            }
+            Some(d) if Self::savepoint_released(conn, d - 1) => {
+                let res = conn
+                    .raw
+                    .rollback()
+                    .map_err(ErrorHelper::from)
+                    .map_err(Into::into);
+                conn.track_connection_loss(&res);
                 res
             }
             Some(d) => {
@@ -108,6 +251,11 @@ impl TransactionManager<OciConnection> for OCITransactionManager {
             None => Err(diesel::result::Error::NotInTransaction),
         }?;
         let res = Self::change_transaction_depth(conn, TransactionDepthChange::DecreaseDepth);
+        match transaction_depth.map(NonZeroU32::get) {
+            Some(1) => conn.transaction_manager.transaction_finished(false),
+            Some(d) => conn.transaction_manager.savepoint_rolled_back(d),
+            None => {}
+        }
         if mark_as_broken {
             let status = Self::transaction_manager_status_mut(conn);
             *status = diesel::connection::TransactionManagerStatus::InError;
@@ -126,20 +274,37 @@ impl TransactionManager<OciConnection> for OCITransactionManager {
         // rolled back, every inner transaction can fail, but no be committed since it doesn't make
         // sense to commit the inner ones
         match transaction_depth.map(Into::into) {
+            Some(1) if conn.transaction_manager.xid.is_some() => Err(Error::QueryBuilderError(
+                "Distributed transactions need to be completed via \
+                     `tpc_prepare` and `tpc_commit`"
+                    .into(),
+            )),
             Some(1) => {
-                if let Err(e) = conn.raw.commit().map_err(ErrorHelper::from) {
+                let res = conn
+                    .raw
+                    .commit()
+                    .map_err(ErrorHelper::from)
+                    .map_err(Into::into);
+                conn.track_connection_loss(&res);
+                if let Err(e) = res {
                     let status = Self::transaction_manager_status_mut(conn);
                     *status = diesel::connection::TransactionManagerStatus::InError;
-                    return Err(e.into());
+                    conn.transaction_manager.transaction_finished(false);
+                    return Err(e);
                 }
-                conn.raw.set_autocommit(true);
+                conn.raw.set_autocommit(conn.autocommit);
+                Self::change_transaction_depth(conn, TransactionDepthChange::DecreaseDepth)?;
+                conn.transaction_manager.transaction_finished(true);
+                Ok(())
             }
-            Some(_) => {
+            Some(d) => {
                 // Do nothing for savepoints
+                Self::change_transaction_depth(conn, TransactionDepthChange::DecreaseDepth)?;
+                conn.transaction_manager.savepoint_committed(d);
+                Ok(())
             }
-            None => return Err(diesel::result::Error::NotInTransaction),
+            None => Err(diesel::result::Error::NotInTransaction),
         }
-        Self::change_transaction_depth(conn, TransactionDepthChange::DecreaseDepth)
     }
 
     fn transaction_manager_status_mut(
@@ -161,3 +326,237 @@ impl TransactionManager<OciConnection> for OCITransactionManager {
         }
     }
 }
+
+/// Used to build a transaction, specifying additional details.
+///
+/// This struct is returned by [`OciConnection::build_transaction`].
+/// See the documentation for methods on this struct for usage examples.
+/// See [the Oracle documentation for `SET TRANSACTION`][docs]
+/// for details on the behavior of each option.
+///
+/// [docs]: https://docs.oracle.com/en/database/oracle/oracle-database/19/sqlrf/SET-TRANSACTION.html
+#[allow(missing_debug_implementations)]
+#[must_use = "Transaction builder does nothing unless you call `run` on it"]
+pub struct OciTransactionBuilder<'a> {
+    connection: &'a mut OciConnection,
+    isolation_level: Option<IsolationLevel>,
+    read_mode: Option<ReadMode>,
+    name: Option<String>,
+    deferrable_constraints: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IsolationLevel {
+    ReadCommitted,
+    Serializable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReadMode {
+    ReadOnly,
+    ReadWrite,
+}
+
+impl<'a> OciTransactionBuilder<'a> {
+    pub(super) fn new(connection: &'a mut OciConnection) -> Self {
+        Self {
+            connection,
+            isolation_level: None,
+            read_mode: None,
+            name: None,
+            deferrable_constraints: false,
+        }
+    }
+
+    /// Makes the transaction `READ ONLY`
+    ///
+    /// All queries in the transaction see the database as of the
+    /// start of the transaction, which allows consistent reports
+    /// over multiple queries.
+    ///
+    /// Oracle does not allow to combine this with an isolation level.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use diesel_oci::OciConnection;
+    /// # use diesel::prelude::*;
+    /// # fn run(conn: &mut OciConnection) -> QueryResult<()> {
+    /// conn.build_transaction()
+    ///     .read_only()
+    ///     .run(|conn| {
+    ///         diesel::sql_query("SELECT 1 FROM DUAL").execute(conn)
+    ///     })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_only(mut self) -> Self {
+        self.read_mode = Some(ReadMode::ReadOnly);
+        self
+    }
+
+    /// Makes the transaction `READ WRITE`
+    ///
+    /// This is the default, unless you've changed the
+    /// default for the session.
+    pub fn read_write(mut self) -> Self {
+        self.read_mode = Some(ReadMode::ReadWrite);
+        self
+    }
+
+    /// Sets the transaction isolation level to `SERIALIZABLE`
+    ///
+    /// Statements that modify rows changed by other transactions after
+    /// the start of this transaction fail with
+    /// [`DatabaseErrorKind::SerializationFailure`](diesel::result::DatabaseErrorKind::SerializationFailure)
+    pub fn serializable(mut self) -> Self {
+        self.isolation_level = Some(IsolationLevel::Serializable);
+        self
+    }
+
+    /// Sets the transaction isolation level to `READ COMMITTED`
+    ///
+    /// This is the default, unless you've changed the
+    /// default for the session.
+    pub fn read_committed(mut self) -> Self {
+        self.isolation_level = Some(IsolationLevel::ReadCommitted);
+        self
+    }
+
+    /// Sets the name of the transaction
+    ///
+    /// The name is visible in `V$TRANSACTION` and helps to identify
+    /// long running or in-doubt transactions.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Defers the checks of all deferrable constraints to the end of the transaction
+    ///
+    /// This emits `SET CONSTRAINTS ALL DEFERRED` at the start of the transaction
+    pub fn deferrable_constraints(mut self) -> Self {
+        self.deferrable_constraints = true;
+        self
+    }
+
+    /// The statements used to start the transaction
+    pub(crate) fn statements(&self) -> QueryResult<Vec<String>> {
+        let mut statements = Vec::new();
+        let mut set_transaction = String::from("SET TRANSACTION");
+        match (self.read_mode, self.isolation_level) {
+            (Some(_), Some(_)) => {
+                return Err(Error::QueryBuilderError(
+                    "Oracle does not support to combine a read mode with an isolation level".into(),
+                ))
+            }
+            (Some(ReadMode::ReadOnly), None) => set_transaction += " READ ONLY",
+            (Some(ReadMode::ReadWrite), None) => set_transaction += " READ WRITE",
+            (None, Some(IsolationLevel::Serializable)) => {
+                set_transaction += " ISOLATION LEVEL SERIALIZABLE"
+            }
+            (None, Some(IsolationLevel::ReadCommitted)) => {
+                set_transaction += " ISOLATION LEVEL READ COMMITTED"
+            }
+            (None, None) => {}
+        }
+        if let Some(ref name) = self.name {
+            set_transaction += " NAME '";
+            set_transaction += &name.replace('\'', "''");
+            set_transaction.push('\'');
+        }
+        if set_transaction != "SET TRANSACTION" {
+            statements.push(set_transaction);
+        }
+        if self.deferrable_constraints {
+            statements.push(String::from("SET CONSTRAINTS ALL DEFERRED"));
+        }
+        Ok(statements)
+    }
+
+    /// Runs the given function inside of the transaction
+    /// with the parameters given to this builder.
+    ///
+    /// This function executes the provided closure `f` inside a database
+    /// transaction. If there is already an open transaction for the current
+    /// connection it will return an error. The connection is committed if
+    /// the closure returns `Ok(_)`, it will be rolled back if it returns `Err(_)`.
+    /// For both cases the original result value will be returned from this function.
+    ///
+    /// Nested calls to [`Connection::transaction`](diesel::Connection::transaction)
+    /// inside the closure use savepoints as usual.
+    pub fn run<T, E, F>(&mut self, f: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut OciConnection) -> Result<T, E>,
+        E: From<Error>,
+    {
+        if OCITransactionManager::get_transaction_depth(self.connection)?.is_some() {
+            return Err(Error::AlreadyInTransaction.into());
+        }
+        let mut statements = self.statements()?.into_iter();
+        match statements.next() {
+            Some(first) => OCITransactionManager::begin_transaction_sql(self.connection, &first)?,
+            None => OCITransactionManager::begin_transaction(self.connection)?,
+        }
+        let res = statements.try_for_each(|sql| self.connection.batch_execute(&sql));
+        let res = match res {
+            Ok(()) => f(&mut *self.connection),
+            Err(e) => Err(e.into()),
+        };
+        match res {
+            Ok(value) => {
+                OCITransactionManager::commit_transaction(self.connection)?;
+                Ok(value)
+            }
+            Err(user_error) => match OCITransactionManager::rollback_transaction(self.connection) {
+                Ok(()) | Err(Error::BrokenTransactionManager) => Err(user_error),
+                Err(rollback_error) => Err(rollback_error.into()),
+            },
+        }
+    }
+}
+
+fn run_callbacks(callbacks: Vec<PendingCallback>, committed: bool) {
+    for pending in callbacks {
+        if pending.on_commit == committed {
+            (pending.callback)();
+        }
+    }
+}
+
+/// Checks whether the given statement implicitly commits the open transaction
+///
+/// This is the case for all DDL statements, except for
+/// `ALTER SESSION` and `ALTER SYSTEM`. Only the head of the statement
+/// after leading comments is inspected, so keywords in string literals,
+/// comments or subqueries are ignored.
+pub(crate) fn causes_implicit_commit(sql: &str) -> bool {
+    let (first, rest) = next_keyword(sql);
+    match first.to_ascii_uppercase().as_str() {
+        "CREATE" | "DROP" | "TRUNCATE" | "RENAME" | "GRANT" | "REVOKE" | "COMMENT" | "AUDIT"
+        | "NOAUDIT" | "ANALYZE" | "PURGE" | "FLASHBACK" | "ASSOCIATE" | "DISASSOCIATE" => true,
+        "ALTER" => {
+            let (second, _) = next_keyword(rest);
+            !second.eq_ignore_ascii_case("SESSION") && !second.eq_ignore_ascii_case("SYSTEM")
+        }
+        _ => false,
+    }
+}
+
+/// Splits off the next keyword, skipping whitespace and comments
+fn next_keyword(mut sql: &str) -> (&str, &str) {
+    loop {
+        sql = sql.trim_start();
+        if let Some(comment) = sql.strip_prefix("--") {
+            sql = comment.split_once('\n').map_or("", |(_, rest)| rest);
+        } else if let Some(comment) = sql.strip_prefix("/*") {
+            sql = comment.split_once("*/").map_or("", |(_, rest)| rest);
+        } else {
+            break;
+        }
+    }
+    let end = sql
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(sql.len());
+    sql.split_at(end)
+}
@@ -11,8 +11,19 @@ pub mod query_builder;
 pub(crate) mod query_dsl;
 pub(crate) mod types;
 
-pub use self::backend::Oracle;
-pub use self::connection::{OciConnection, OracleValue};
+pub use self::backend::{OciPaginationMode, OciServerVersion, Oracle};
+#[cfg(feature = "r2d2")]
+pub use self::connection::OciResetSession;
+pub use self::connection::{
+    OciCancelHandle, OciConnectOptions, OciConnection, OciCredentialProvider, OciCredentials,
+    OciDatabaseError, OciDdlPolicy, OciErrorExt, OciPool, OciPoolBuilder, OciPrivilege,
+    OciRetryPolicy, OciRowByRowLoadingMode, OciTransactionBuilder, OciXid, OracleValue,
+};
 pub use self::types::{
     OciDataType, OciIntervalDS, OciIntervalYM, OciTypeMetadata, SqlIntervalDS, SqlIntervalYM,
+    SqlTimestamptz,
 };
+
+pub use self::query_dsl::{OciFetchDsl, WithFetchOptions};
+#[cfg(feature = "chrono-time")]
+pub use self::types::OciTimestamptz;
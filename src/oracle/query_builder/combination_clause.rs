@@ -0,0 +1,81 @@
+//! Support for diesels `CombineDsl`
+//!
+//! Diesel writes the set operators itself, which does not allow a
+//! third party backend to choose other keywords. Oracle accepts `EXCEPT`
+//! only from 21c on, while `MINUS` is known by all releases. The
+//! [`OciQueryBuilder`](super::OciQueryBuilder) therefore replaces `EXCEPT`
+//! by `MINUS` while the query is written. `EXCEPT ALL` and `INTERSECT ALL`
+//! (written as `MINUS ALL` and `INTERSECT ALL`) require 21c, this is checked
+//! against the server version once the query is complete.
+
+use super::OciDialect;
+use crate::oracle::backend::Oracle;
+
+use diesel::query_builder::{
+    All, Distinct, Except, Intersect, ParenthesisWrapper, SupportsCombinationClause, Union,
+};
+use diesel::query_builder::{AstPass, QueryFragment};
+use diesel::result::{Error, QueryResult};
+
+const EXCEPT: &str = " EXCEPT ";
+const MINUS: &str = " MINUS ";
+const INTERSECT: &str = " INTERSECT ";
+const ALL: &str = "ALL ";
+
+impl<T: QueryFragment<Oracle>> QueryFragment<Oracle> for ParenthesisWrapper<T> {
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Oracle>) -> QueryResult<()> {
+        out.push_sql("(");
+        self.inner.walk_ast(out.reborrow())?;
+        out.push_sql(")");
+        Ok(())
+    }
+}
+
+impl SupportsCombinationClause<Union, Distinct> for Oracle {}
+impl SupportsCombinationClause<Union, All> for Oracle {}
+impl SupportsCombinationClause<Intersect, Distinct> for Oracle {}
+impl SupportsCombinationClause<Intersect, All> for Oracle {}
+impl SupportsCombinationClause<Except, Distinct> for Oracle {}
+impl SupportsCombinationClause<Except, All> for Oracle {}
+
+/// Tracks the set operators written to the query builder
+#[derive(Default)]
+pub(super) struct SetOperators {
+    /// The last written sql was `MINUS` or `INTERSECT`
+    last: Option<&'static str>,
+    /// The first `MINUS ALL` or `INTERSECT ALL` of the query
+    with_all: Option<&'static str>,
+}
+
+impl SetOperators {
+    /// Returns the sql to write instead of `sql`
+    pub(super) fn push_sql<'a>(&mut self, sql: &'a str) -> &'a str {
+        match (self.last.take(), sql) {
+            (_, EXCEPT) => {
+                self.last = Some("MINUS ALL");
+                MINUS
+            }
+            (_, INTERSECT) => {
+                self.last = Some("INTERSECT ALL");
+                sql
+            }
+            (Some(operator), ALL) => {
+                self.with_all.get_or_insert(operator);
+                sql
+            }
+            _ => sql,
+        }
+    }
+
+    /// Checks that `dialect` supports the written set operators
+    pub(super) fn check(&self, dialect: &OciDialect) -> QueryResult<()> {
+        match self.with_all {
+            Some(operator) if !dialect.supports_set_operators_with_all() => {
+                Err(Error::QueryBuilderError(
+                    format!("`{operator}` requires Oracle 21c or newer").into(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! The sql dialect a query is written in
+//!
+//! [`Oracle`](crate::oracle::Oracle) is a unit type, so the backend passed to
+//! `QueryFragment::walk_ast` cannot tell which server a query is written for.
+//! The connection therefore makes its dialect available while it builds a
+//! query, see [`OciDialect::scope`]. Queries built outside of such a scope
+//! (for example by `diesel::debug_query`) use a dialect supported by all
+//! releases since Oracle 12c.
+
+use std::cell::Cell;
+
+use crate::oracle::backend::{OciPaginationMode, OciServerVersion};
+
+thread_local! {
+    static CURRENT: Cell<Option<OciDialect>> = const { Cell::new(None) };
+}
+
+/// The sql dialect settings of a connection
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OciDialect {
+    pagination_mode: OciPaginationMode,
+    server_version: Option<OciServerVersion>,
+}
+
+impl OciDialect {
+    pub(crate) fn with_pagination_mode(mut self, mode: OciPaginationMode) -> Self {
+        self.pagination_mode = mode;
+        self
+    }
+
+    pub(crate) fn pagination_mode(&self) -> OciPaginationMode {
+        self.pagination_mode
+    }
+
+    pub(crate) fn with_server_version(mut self, version: OciServerVersion) -> Self {
+        self.server_version = Some(version);
+        self
+    }
+
+    fn server_is_at_least(&self, major: i32) -> bool {
+        self.server_version.is_some_and(|v| v.major() >= major)
+    }
+
+    /// Predicates are boolean values on their own (23ai)
+    pub(crate) fn has_native_boolean(&self) -> bool {
+        self.server_is_at_least(23)
+    }
+
+    /// `MINUS ALL` and `INTERSECT ALL` are supported (21c)
+    pub(crate) fn supports_set_operators_with_all(&self) -> bool {
+        self.server_is_at_least(21)
+    }
+
+    /// `SELECT` statements do not require a `FROM` clause (23ai)
+    pub(crate) fn supports_select_without_from(&self) -> bool {
+        self.server_is_at_least(23)
+    }
+
+    /// The dialect of the query built on this thread
+    pub(crate) fn current() -> Self {
+        CURRENT.with(Cell::get).unwrap_or_default()
+    }
+
+    /// Builds queries in this dialect while `f` runs
+    ///
+    /// This has to cover every pass over the query (`to_sql`,
+    /// `collect_binds`, `is_safe_to_cache_prepared`), as the
+    /// written sql and the collected binds depend on the dialect
+    pub(crate) fn scope<R>(self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<OciDialect>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.with(|current| current.set(self.0));
+            }
+        }
+
+        let _restore = Restore(CURRENT.with(|current| current.replace(Some(self))));
+        f()
+    }
+}
@@ -1,9 +1,164 @@
+use super::OciDialect;
+use crate::oracle::backend::OciPaginationMode;
 use crate::oracle::Oracle;
 use diesel::query_builder::{AstPass, QueryFragment};
 use diesel::query_builder::{BoxedLimitOffsetClause, IntoBoxedClause, LimitOffsetClause};
 use diesel::query_builder::{LimitClause, NoLimitClause};
 use diesel::query_builder::{NoOffsetClause, OffsetClause};
-use diesel::result::QueryResult;
+use diesel::result::{Error, QueryResult};
+
+const FETCH_FIRST: &str = " FETCH FIRST ";
+const OFFSET: &str = " OFFSET ";
+const ROWS_FETCH_NEXT: &str = " ROWS FETCH NEXT ";
+const ROWNUM_OFFSET_START: &str = ") a WHERE ROWNUM <= (";
+const ROWNUM_PLUS: &str = " + ";
+const ROWNUM_OFFSET_END: &str = ")) WHERE rnum > ";
+const ROWNUM_LIMIT: &str = ") WHERE ROWNUM <= ";
+const ROWNUM_ONLY_OFFSET: &str = ") a) WHERE rnum > ";
+
+/// `limit` and `offset` calls on boxed select statements store diesels
+/// `LimitClause`/`OffsetClause`, which write ` LIMIT `/` OFFSET ` in front
+/// of the value. Oracle does not know these keywords, so the query builder
+/// skips them if they directly follow one of the fragments written in front
+/// of a limit or offset value here.
+pub(super) fn is_followed_by_value(sql: &str) -> bool {
+    [
+        FETCH_FIRST,
+        OFFSET,
+        ROWS_FETCH_NEXT,
+        ROWNUM_OFFSET_START,
+        ROWNUM_PLUS,
+        ROWNUM_OFFSET_END,
+        ROWNUM_LIMIT,
+        ROWNUM_ONLY_OFFSET,
+    ]
+    .contains(&sql)
+}
+
+/// Whether `sql` is the keyword diesel writes in front of limit and offset values
+pub(super) fn is_limit_offset_keyword(sql: &str) -> bool {
+    sql == " LIMIT " || sql == OFFSET
+}
+
+/// Access to the limit and the offset of a limit/offset clause
+///
+/// The `ROWNUM` based pagination writes them at different places of the
+/// query, see [`OciPaginationMode::RowNum`]
+pub trait OracleLimitOffset {
+    /// The limit of this clause, if any
+    fn limit(&self) -> Option<&dyn QueryFragment<Oracle>>;
+
+    /// The offset of this clause, if any
+    fn offset(&self) -> Option<&dyn QueryFragment<Oracle>>;
+}
+
+impl OracleLimitOffset for LimitOffsetClause<NoLimitClause, NoOffsetClause> {
+    fn limit(&self) -> Option<&dyn QueryFragment<Oracle>> {
+        None
+    }
+
+    fn offset(&self) -> Option<&dyn QueryFragment<Oracle>> {
+        None
+    }
+}
+
+impl<L> OracleLimitOffset for LimitOffsetClause<LimitClause<L>, NoOffsetClause>
+where
+    L: QueryFragment<Oracle>,
+{
+    fn limit(&self) -> Option<&dyn QueryFragment<Oracle>> {
+        Some(&self.limit_clause.0)
+    }
+
+    fn offset(&self) -> Option<&dyn QueryFragment<Oracle>> {
+        None
+    }
+}
+
+impl<O> OracleLimitOffset for LimitOffsetClause<NoLimitClause, OffsetClause<O>>
+where
+    O: QueryFragment<Oracle>,
+{
+    fn limit(&self) -> Option<&dyn QueryFragment<Oracle>> {
+        None
+    }
+
+    fn offset(&self) -> Option<&dyn QueryFragment<Oracle>> {
+        Some(&self.offset_clause.0)
+    }
+}
+
+impl<L, O> OracleLimitOffset for LimitOffsetClause<LimitClause<L>, OffsetClause<O>>
+where
+    L: QueryFragment<Oracle>,
+    O: QueryFragment<Oracle>,
+{
+    fn limit(&self) -> Option<&dyn QueryFragment<Oracle>> {
+        Some(&self.limit_clause.0)
+    }
+
+    fn offset(&self) -> Option<&dyn QueryFragment<Oracle>> {
+        Some(&self.offset_clause.0)
+    }
+}
+
+impl OracleLimitOffset for BoxedLimitOffsetClause<'_, Oracle> {
+    fn limit(&self) -> Option<&dyn QueryFragment<Oracle>> {
+        self.limit.as_deref().map(|l| l as _)
+    }
+
+    fn offset(&self) -> Option<&dyn QueryFragment<Oracle>> {
+        self.offset.as_deref().map(|o| o as _)
+    }
+}
+
+/// Writes `query` wrapped into the `ROWNUM` filters for the given limit and offset
+pub(super) fn walk_rownum_pagination<'b>(
+    limit_offset: &'b dyn OracleLimitOffset,
+    mut out: AstPass<'_, 'b, Oracle>,
+    query: impl FnOnce(AstPass<'_, 'b, Oracle>) -> QueryResult<()>,
+) -> QueryResult<()> {
+    match (limit_offset.limit(), limit_offset.offset()) {
+        (Some(limit), Some(offset)) => {
+            out.push_sql("SELECT * FROM (SELECT a.*, ROWNUM rnum FROM (");
+            query(out.reborrow())?;
+            out.push_sql(ROWNUM_OFFSET_START);
+            offset.walk_ast(out.reborrow())?;
+            out.push_sql(ROWNUM_PLUS);
+            limit.walk_ast(out.reborrow())?;
+            out.push_sql(ROWNUM_OFFSET_END);
+            offset.walk_ast(out.reborrow())?;
+        }
+        (Some(limit), None) => {
+            out.push_sql("SELECT * FROM (");
+            query(out.reborrow())?;
+            out.push_sql(ROWNUM_LIMIT);
+            limit.walk_ast(out.reborrow())?;
+        }
+        (None, Some(offset)) => {
+            out.push_sql("SELECT * FROM (SELECT a.*, ROWNUM rnum FROM (");
+            query(out.reborrow())?;
+            out.push_sql(ROWNUM_ONLY_OFFSET);
+            offset.walk_ast(out.reborrow())?;
+        }
+        (None, None) => query(out.reborrow())?,
+    }
+    Ok(())
+}
+
+/// Limit and offset are handled by the select statement itself for
+/// the `ROWNUM` based pagination, other places (like combined
+/// queries) cannot be rewritten that way
+fn check_row_limiting_clause() -> QueryResult<()> {
+    if OciDialect::current().pagination_mode() == OciPaginationMode::RowNum {
+        return Err(Error::QueryBuilderError(
+            "The `ROWNUM` based pagination only supports `LIMIT` and `OFFSET` \
+             on select statements"
+                .into(),
+        ));
+    }
+    Ok(())
+}
 
 impl QueryFragment<Oracle> for LimitOffsetClause<NoLimitClause, NoOffsetClause> {
     fn walk_ast(&self, _out: AstPass<Oracle>) -> QueryResult<()> {
@@ -16,7 +171,8 @@ where
     L: QueryFragment<Oracle>,
 {
     fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Oracle>) -> QueryResult<()> {
-        out.push_sql(" FETCH FIRST ");
+        check_row_limiting_clause()?;
+        out.push_sql(FETCH_FIRST);
         self.limit_clause.0.walk_ast(out.reborrow())?;
         out.push_sql(" ROWS ONLY ");
         Ok(())
@@ -28,7 +184,8 @@ where
     O: QueryFragment<Oracle>,
 {
     fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Oracle>) -> QueryResult<()> {
-        out.push_sql(" OFFSET ");
+        check_row_limiting_clause()?;
+        out.push_sql(OFFSET);
         self.offset_clause.0.walk_ast(out.reborrow())?;
         out.push_sql(" ROWS ");
         Ok(())
@@ -41,9 +198,10 @@ where
     O: QueryFragment<Oracle>,
 {
     fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Oracle>) -> QueryResult<()> {
-        out.push_sql(" OFFSET ");
+        check_row_limiting_clause()?;
+        out.push_sql(OFFSET);
         self.offset_clause.0.walk_ast(out.reborrow())?;
-        out.push_sql(" ROWS FETCH NEXT ");
+        out.push_sql(ROWS_FETCH_NEXT);
         self.limit_clause.0.walk_ast(out.reborrow())?;
         out.push_sql(" ROWS ONLY ");
         Ok(())
@@ -52,21 +210,24 @@ where
 
 impl<'a> QueryFragment<Oracle> for BoxedLimitOffsetClause<'a, Oracle> {
     fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Oracle>) -> QueryResult<()> {
+        if self.limit.is_some() || self.offset.is_some() {
+            check_row_limiting_clause()?;
+        }
         match (self.limit.as_ref(), self.offset.as_ref()) {
             (Some(limit), Some(offset)) => {
-                out.push_sql(" OFFSET ");
+                out.push_sql(OFFSET);
                 offset.walk_ast(out.reborrow())?;
-                out.push_sql(" ROWS FETCH NEXT ");
+                out.push_sql(ROWS_FETCH_NEXT);
                 limit.walk_ast(out.reborrow())?;
                 out.push_sql(" ROWS ONLY ");
             }
             (Some(limit), None) => {
-                out.push_sql(" FETCH FIRST ");
+                out.push_sql(FETCH_FIRST);
                 limit.walk_ast(out.reborrow())?;
                 out.push_sql(" ROWS ONLY ");
             }
             (None, Some(offset)) => {
-                out.push_sql(" OFFSET ");
+                out.push_sql(OFFSET);
                 offset.walk_ast(out.reborrow())?;
                 out.push_sql(" ROWS ");
             }
@@ -1,24 +1,47 @@
 use super::backend::Oracle;
 use super::backend::OracleDualForEmptySelectClause;
+use super::query_dsl::FetchOptions;
+
+use std::mem;
 
 use diesel::query_builder::NoFromClause;
 use diesel::query_builder::QueryBuilder;
 use diesel::query_builder::QueryFragment;
 use diesel::result::Error as DieselError;
+use diesel::result::QueryResult;
 
 mod alias;
-mod exists;
+mod combination_clause;
+mod dialect;
 mod limit_offset;
 mod returning;
+mod select_list;
+mod select_statement;
+mod upsert;
 
 pub use self::alias::{Alias, As};
 pub use self::returning::BindColumnList;
 
+pub(crate) use self::dialect::OciDialect;
+
+use self::combination_clause::SetOperators;
+use self::select_list::SelectLists;
+use self::upsert::Upsert;
+
 /// The Oracle query builder
 #[derive(Default)]
 pub struct OciQueryBuilder {
     pub(crate) sql: String,
     bind_idx: u32,
+    dialect: OciDialect,
+    /// The last fragment is followed by a limit or offset value,
+    /// see `limit_offset::is_followed_by_value`
+    expects_limit_offset_value: bool,
+    upsert: Upsert,
+    set_operators: SetOperators,
+    select_lists: SelectLists,
+    /// Settings for the statement prepared for the query, see `OciFetchDsl`
+    pub(crate) fetch_options: FetchOptions,
 }
 
 impl OciQueryBuilder {
@@ -27,20 +50,55 @@ impl OciQueryBuilder {
         OciQueryBuilder {
             sql: String::new(),
             bind_idx: 0,
+            dialect: OciDialect::default(),
+            expects_limit_offset_value: false,
+            upsert: Upsert::None,
+            set_operators: SetOperators::default(),
+            select_lists: SelectLists::default(),
+            fetch_options: FetchOptions::default(),
         }
     }
+
+    /// Constructs a new query builder writing the given sql dialect
+    pub(crate) fn for_dialect(dialect: OciDialect) -> Self {
+        OciQueryBuilder {
+            dialect,
+            select_lists: SelectLists::new(!dialect.has_native_boolean()),
+            ..Self::new()
+        }
+    }
+
+    /// Checks that Oracle and the server version of the dialect support
+    /// the written query
+    ///
+    /// Some parts of diesels query dsl can only be detected while the
+    /// query is written, this has to be called before executing a query
+    pub(crate) fn check_server_support(&self) -> QueryResult<()> {
+        self.set_operators.check(&self.dialect)?;
+        self.upsert.check()
+    }
 }
 
 impl QueryBuilder<Oracle> for OciQueryBuilder {
     fn push_sql(&mut self, sql: &str) {
-        self.sql.push_str(sql);
+        let expects_value = mem::replace(
+            &mut self.expects_limit_offset_value,
+            limit_offset::is_followed_by_value(sql),
+        );
+        if expects_value && limit_offset::is_limit_offset_keyword(sql) {
+            self.expects_limit_offset_value = false;
+            return;
+        }
+        let sql = self.set_operators.push_sql(sql);
+        let upsert = &mut self.upsert;
+        self.select_lists
+            .push_sql(&mut self.sql, sql, |out, sql| upsert.push_sql(out, sql));
     }
 
     fn push_identifier(&mut self, identifier: &str) -> Result<(), DieselError> {
         // TODO: check if there is a better way for escaping strings
-        self.push_sql("\"");
-        self.push_sql(&identifier.replace('`', "``").to_uppercase());
-        self.push_sql("\"");
+        let identifier = format!("\"{}\"", identifier.replace('`', "``").to_uppercase());
+        self.upsert.push_identifier(&mut self.sql, &identifier);
         Ok(())
     }
 
@@ -50,13 +108,18 @@ impl QueryBuilder<Oracle> for OciQueryBuilder {
         self.push_sql(&sql);
     }
 
-    fn finish(self) -> String {
+    fn finish(mut self) -> String {
+        self.select_lists.finish(&mut self.sql);
+        self.upsert.finish(&mut self.sql);
         self.sql
     }
 }
 
 impl QueryFragment<Oracle, OracleDualForEmptySelectClause> for NoFromClause {
     fn walk_ast(&self, mut out: diesel::query_builder::AstPass<Oracle>) -> diesel::QueryResult<()> {
+        if OciDialect::current().supports_select_without_from() {
+            return Ok(());
+        }
         out.push_sql(" FROM DUAL ");
         Ok(())
     }
@@ -0,0 +1,219 @@
+//! Boolean expressions in select clauses
+//!
+//! Oracle releases before 23ai have no boolean values in sql. Predicates
+//! like `a = b`, `a IS NULL` or `EXISTS (...)` are only allowed as
+//! conditions (`WHERE`, `ON`, `HAVING`, `CASE WHEN`, ...), so selecting
+//! one of them is written as
+//!
+//! ```sql
+//! SELECT CASE WHEN "T"."A" = :in0 THEN 1 ELSE 0 END FROM "T"
+//! ```
+//!
+//! which is read back as `bool`. A predicate evaluating to `NULL` is
+//! selected as `0` that way.
+//!
+//! Diesel writes these expressions with backend independent
+//! implementations, so we track the select clauses while they are
+//! written to the query builder. Each selected expression containing a
+//! predicate operator outside of function calls, `CASE` expressions and
+//! subqueries is wrapped once it is complete. Bool columns are numbers in
+//! Oracle and are selected as they are.
+//!
+//! The query builder only sees the written sql, not the sql type of the
+//! selected expressions. Boolean expressions that do not use one of the
+//! operators in [`PREDICATES`], like `sql::<Bool>("a = b")` literals or
+//! custom infix operators, are therefore not wrapped and are rejected by
+//! servers before 23ai.
+
+use std::mem;
+
+const SELECT: &str = "SELECT ";
+const DISTINCT: &str = "DISTINCT ";
+const SEPARATOR: &str = ", ";
+const FROM: &str = " FROM ";
+const FROM_DUAL: &str = " FROM DUAL ";
+const CASE: &str = "CASE";
+const END: &str = " END";
+const GROUP: &str = "(";
+
+/// The sql written by diesel for predicates
+const PREDICATES: &[&str] = &[
+    " AND ",
+    " OR ",
+    " NOT ",
+    " = ",
+    " != ",
+    " < ",
+    " <= ",
+    " > ",
+    " >= ",
+    " LIKE ",
+    " NOT LIKE ",
+    " BETWEEN ",
+    " NOT BETWEEN ",
+    " IS NULL",
+    " IS NOT NULL",
+    " IN (",
+    " NOT IN (",
+    "EXISTS (",
+    "1=0",
+    "1=1",
+];
+
+pub(super) struct SelectLists {
+    enabled: bool,
+    /// The select statements containing the sql written next,
+    /// the innermost one is the last one
+    statements: Vec<SelectStatement>,
+}
+
+struct SelectStatement {
+    /// The selected expression that is written currently,
+    /// `None` once the select clause is complete
+    item: Option<Item>,
+    /// The open parentheses and `CASE` expressions of this statement
+    nesting: Vec<Nesting>,
+}
+
+struct Item {
+    start: usize,
+    predicate: bool,
+}
+
+#[derive(PartialEq)]
+enum Nesting {
+    /// Parentheses grouping an expression, `(a = b)` is still a predicate
+    Group,
+    /// Function calls, lists and `CASE` expressions
+    Value,
+}
+
+impl Default for SelectLists {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+impl SelectLists {
+    /// `enabled` is false for servers with native boolean support
+    pub(super) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            statements: Vec::new(),
+        }
+    }
+
+    /// Writes `sql` via `write`, wrapping the selected expression written before if required
+    pub(super) fn push_sql(
+        &mut self,
+        out: &mut String,
+        sql: &str,
+        write: impl FnOnce(&mut String, &str),
+    ) {
+        if !self.enabled {
+            return write(out, sql);
+        }
+        if let Some(statement) = self.statements.last_mut() {
+            let top_level = statement.nesting.is_empty();
+            let predicate_allowed = statement.nesting.iter().all(|n| *n == Nesting::Group);
+            match (&mut statement.item, sql) {
+                (Some(item), SEPARATOR) if top_level => item.finish(out),
+                (Some(item), FROM | FROM_DUAL) if top_level => {
+                    item.finish(out);
+                    statement.item = None;
+                }
+                (Some(item), _) if predicate_allowed && PREDICATES.contains(&sql) => {
+                    item.predicate = true;
+                }
+                _ => {}
+            }
+        }
+        match sql {
+            CASE => self.open(Nesting::Value),
+            END => self.close(out),
+            _ => {
+                for c in sql.chars() {
+                    match c {
+                        '(' if sql == GROUP => self.open(Nesting::Group),
+                        '(' => self.open(Nesting::Value),
+                        ')' => self.close(out),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        write(out, sql);
+        match sql {
+            SELECT => self.statements.push(SelectStatement {
+                item: Some(Item::new(out.len())),
+                nesting: Vec::new(),
+            }),
+            SEPARATOR | DISTINCT => {
+                if let Some(SelectStatement {
+                    item: Some(item),
+                    nesting,
+                }) = self.statements.last_mut()
+                {
+                    if nesting.is_empty() && (sql == SEPARATOR || item.is_empty(out, sql)) {
+                        *item = Item::new(out.len());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Wraps the selected expressions of statements that are not complete yet
+    pub(super) fn finish(&mut self, out: &mut String) {
+        for statement in mem::take(&mut self.statements).into_iter().rev() {
+            if let Some(mut item) = statement.item {
+                item.finish(out);
+            }
+        }
+    }
+
+    fn open(&mut self, nesting: Nesting) {
+        if let Some(statement) = self.statements.last_mut() {
+            statement.nesting.push(nesting);
+        }
+    }
+
+    fn close(&mut self, out: &mut String) {
+        let Some(statement) = self.statements.last_mut() else {
+            return;
+        };
+        if statement.nesting.pop().is_some() {
+            return;
+        }
+        // the parenthesis closes a subquery, which also closes
+        // the parenthesis opened in the outer statement
+        if let Some(mut item) = statement.item.take() {
+            item.finish(out);
+        }
+        self.statements.pop();
+        if let Some(outer) = self.statements.last_mut() {
+            outer.nesting.pop();
+        }
+    }
+}
+
+impl Item {
+    fn new(start: usize) -> Self {
+        Self {
+            start,
+            predicate: false,
+        }
+    }
+
+    /// Whether only `sql` was written since this item started
+    fn is_empty(&self, out: &str, sql: &str) -> bool {
+        self.start + sql.len() == out.len()
+    }
+
+    fn finish(&mut self, out: &mut String) {
+        if mem::take(&mut self.predicate) {
+            out.insert_str(self.start, "CASE WHEN ");
+            out.push_str(" THEN 1 ELSE 0 END");
+        }
+    }
+}
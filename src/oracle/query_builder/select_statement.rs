@@ -0,0 +1,84 @@
+use super::limit_offset::{walk_rownum_pagination, OracleLimitOffset};
+use super::OciDialect;
+use crate::oracle::backend::{OciPaginationMode, Oracle, OracleSelectStatementSyntax};
+
+use diesel::query_builder::{AstPass, BoxedSelectStatement, QueryFragment, SelectStatement};
+use diesel::result::{Error, QueryResult};
+
+// These mirror the ansi select statement implementations in diesel,
+// but allow to wrap the query for the `ROWNUM` based pagination
+
+impl<F, S, D, W, O, LOf, G, H, LC> QueryFragment<Oracle, OracleSelectStatementSyntax>
+    for SelectStatement<F, S, D, W, O, LOf, G, H, LC>
+where
+    S: QueryFragment<Oracle>,
+    F: QueryFragment<Oracle>,
+    D: QueryFragment<Oracle>,
+    W: QueryFragment<Oracle>,
+    O: QueryFragment<Oracle>,
+    LOf: QueryFragment<Oracle> + OracleLimitOffset,
+    G: QueryFragment<Oracle>,
+    H: QueryFragment<Oracle>,
+    LC: QueryFragment<Oracle>,
+{
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Oracle>) -> QueryResult<()> {
+        let query = |mut out: AstPass<'_, 'b, Oracle>| {
+            out.push_sql("SELECT ");
+            self.distinct.walk_ast(out.reborrow())?;
+            self.select.walk_ast(out.reborrow())?;
+            self.from.walk_ast(out.reborrow())?;
+            self.where_clause.walk_ast(out.reborrow())?;
+            self.group_by.walk_ast(out.reborrow())?;
+            self.having.walk_ast(out.reborrow())?;
+            self.order.walk_ast(out.reborrow())
+        };
+        if OciDialect::current().pagination_mode() == OciPaginationMode::RowNum {
+            // Oracle rejects `FOR UPDATE` on the wrapping queries (ORA-02014)
+            // and inside of them (ORA-00907)
+            let paginated =
+                self.limit_offset.limit().is_some() || self.limit_offset.offset().is_some();
+            if paginated && !self.locking.is_noop(out.backend())? {
+                return Err(Error::QueryBuilderError(
+                    "The `ROWNUM` based pagination does not support locking clauses \
+                     like `FOR UPDATE` together with `LIMIT` or `OFFSET`"
+                        .into(),
+                ));
+            }
+            walk_rownum_pagination(&self.limit_offset, out.reborrow(), query)?;
+        } else {
+            query(out.reborrow())?;
+            self.limit_offset.walk_ast(out.reborrow())?;
+        }
+        self.locking.walk_ast(out.reborrow())?;
+        Ok(())
+    }
+}
+
+impl<ST, QS, GB> QueryFragment<Oracle, OracleSelectStatementSyntax>
+    for BoxedSelectStatement<'_, ST, QS, Oracle, GB>
+where
+    QS: QueryFragment<Oracle>,
+{
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Oracle>) -> QueryResult<()> {
+        let query = |mut out: AstPass<'_, 'b, Oracle>| {
+            out.push_sql("SELECT ");
+            self.distinct.walk_ast(out.reborrow())?;
+            self.select.walk_ast(out.reborrow())?;
+            self.from.walk_ast(out.reborrow())?;
+            self.where_clause.walk_ast(out.reborrow())?;
+            self.group_by.walk_ast(out.reborrow())?;
+            self.having.walk_ast(out.reborrow())?;
+            if let Some(ref order) = self.order {
+                out.push_sql(" ORDER BY ");
+                order.walk_ast(out.reborrow())?;
+            }
+            Ok(())
+        };
+        if OciDialect::current().pagination_mode() == OciPaginationMode::RowNum {
+            walk_rownum_pagination(&self.limit_offset, out, query)
+        } else {
+            query(out.reborrow())?;
+            self.limit_offset.walk_ast(out)
+        }
+    }
+}
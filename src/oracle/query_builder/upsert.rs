@@ -0,0 +1,311 @@
+//! Support for diesels `on_conflict` API
+//!
+//! Oracle does not know `INSERT ... ON CONFLICT`, upserts are written as
+//! `MERGE INTO` statements there. Diesel renders upserts only in the
+//! postgres style and keeps the individual parts of such a statement
+//! (`OnConflictValues`, `DoUpdate`) private, even for third party backends.
+//! We therefore let diesel render the postgres style statement and track
+//! the relevant parts (table, columns, values, conflict target) while they
+//! are written to the query builder. As soon as the conflict action is known
+//! the statement is restructured into
+//!
+//! ```sql
+//! MERGE INTO "T" USING (SELECT :in0 "A", :in1 "B" FROM DUAL) excluded
+//! ON ("T"."A" = excluded."A")
+//! WHEN MATCHED THEN UPDATE SET "B" = excluded."B"
+//! WHEN NOT MATCHED THEN INSERT ("A", "B") VALUES (excluded."A", excluded."B")
+//! ```
+//!
+//! The source of the merge statement is named `excluded` so that
+//! `diesel::upsert::excluded` works as is. Bind parameters are written in
+//! the same order as in the original statement, so the bind collector does
+//! not need to know anything about this rewrite.
+//!
+//! Some upserts cannot be written as merge statements, these are reported
+//! by [`Upsert::check`] before the query is executed:
+//!
+//! * `on_conflict_do_nothing()` without a conflict target, the merge
+//!   statement needs the columns to join the source on
+//! * conflict targets that are not columns, like `sql()` literals
+//! * upserts with a `RETURNING` clause, which requires Oracle 23ai
+//!
+//! Batch upserts are rejected at compile time (see the `OciConnection` docs).
+//! Batch inserts are executed via custom `ExecuteDsl` implementations (see
+//! `insertable.rs`), which requires that `BatchInsert` does not implement
+//! `QueryFragment<Oracle>`. Diesel can therefore not render an `ON CONFLICT`
+//! clause around them. Reporting a `QueryBuilderError` at runtime instead
+//! would require an `ExecuteDsl` implementation for diesels
+//! `OnConflictValues`, which is not exported.
+
+use std::mem;
+
+use diesel::result::{Error, QueryResult};
+
+const INSERT: &str = "INSERT";
+const INSERT_INTO: &str = "INSERT INTO ";
+const COLUMNS_START: &str = "(";
+const VALUES_START: &str = ") VALUES (";
+const VALUES_END: &str = ")";
+const SEPARATOR: &str = ", ";
+const ON_CONFLICT: &str = " ON CONFLICT";
+const TARGET_START: &str = " (";
+const TARGET_END: &str = ")";
+const DO_UPDATE: &str = " DO UPDATE SET ";
+const DO_NOTHING: &str = " DO NOTHING";
+const RETURNING: &str = " RETURNING ";
+const DEFAULT: &str = "DEFAULT";
+
+#[derive(Default)]
+pub(super) enum Upsert {
+    #[default]
+    None,
+    /// `INSERT` was written at `start`
+    Insert { start: usize },
+    /// The column list of the insert statement is written
+    Columns { statement: InsertStatement },
+    /// The values of the insert statement are written
+    Values {
+        statement: InsertStatement,
+        value_start: usize,
+        depth: usize,
+    },
+    /// A single row insert statement was written completely
+    Inserted { statement: InsertStatement },
+    /// The conflict target is written
+    Target {
+        statement: InsertStatement,
+        target: Vec<String>,
+    },
+    /// The merge statement was written up to the `WHEN MATCHED` clause,
+    /// `insert` is appended once the statement is complete
+    Merge { insert: String },
+    /// The upsert cannot be written as merge statement
+    Unsupported { reason: &'static str },
+}
+
+pub(super) struct InsertStatement {
+    start: usize,
+    table: String,
+    columns: Vec<String>,
+    values: Vec<String>,
+}
+
+impl Upsert {
+    pub(super) fn push_sql(&mut self, out: &mut String, sql: &str) {
+        match (mem::take(self), sql) {
+            (state @ Upsert::Unsupported { .. }, _) => {
+                out.push_str(sql);
+                *self = state;
+            }
+            (Upsert::Insert { start }, COLUMNS_START) => {
+                let table = out
+                    .get(start + INSERT_INTO.len()..)
+                    .unwrap_or_default()
+                    .trim_end()
+                    .to_owned();
+                out.push_str(sql);
+                *self = Upsert::Columns {
+                    statement: InsertStatement {
+                        start,
+                        table,
+                        columns: Vec::new(),
+                        values: Vec::new(),
+                    },
+                };
+            }
+            (Upsert::Columns { statement }, VALUES_START) => {
+                out.push_str(sql);
+                *self = Upsert::Values {
+                    statement,
+                    value_start: out.len(),
+                    depth: 0,
+                };
+            }
+            (
+                Upsert::Values {
+                    mut statement,
+                    value_start,
+                    depth: 0,
+                },
+                SEPARATOR | VALUES_END,
+            ) => {
+                statement.values.push(out[value_start..].to_owned());
+                out.push_str(sql);
+                *self = if sql == SEPARATOR {
+                    Upsert::Values {
+                        statement,
+                        value_start: out.len(),
+                        depth: 0,
+                    }
+                } else {
+                    Upsert::Inserted { statement }
+                };
+            }
+            (
+                Upsert::Values {
+                    statement,
+                    value_start,
+                    depth,
+                },
+                _,
+            ) => {
+                out.push_str(sql);
+                let opened = sql.matches('(').count();
+                let closed = sql.matches(')').count();
+                *self = Upsert::Values {
+                    statement,
+                    value_start,
+                    depth: (depth + opened).saturating_sub(closed),
+                };
+            }
+            (Upsert::Inserted { statement }, ON_CONFLICT) => {
+                *self = Upsert::Target {
+                    statement,
+                    target: Vec::new(),
+                };
+            }
+            (Upsert::Target { target, .. }, DO_UPDATE) if target.is_empty() => {
+                *self = Upsert::Unsupported {
+                    reason: "`do_update()` requires a conflict target",
+                };
+            }
+            (Upsert::Target { statement, target }, DO_UPDATE) => {
+                out.truncate(statement.start);
+                statement.write_merge(out, &target);
+                out.push_str(" WHEN MATCHED THEN UPDATE SET ");
+                *self = Upsert::Merge {
+                    insert: statement.merge_insert(),
+                };
+            }
+            (Upsert::Target { target, .. }, DO_NOTHING) if target.is_empty() => {
+                *self = Upsert::Unsupported {
+                    reason: "`on_conflict_do_nothing()` requires a conflict target, \
+                             use `on_conflict(..).do_nothing()` instead",
+                };
+            }
+            (Upsert::Target { statement, target }, DO_NOTHING) => {
+                out.truncate(statement.start);
+                statement.write_merge(out, &target);
+                *self = Upsert::Merge {
+                    insert: statement.merge_insert(),
+                };
+            }
+            // the parentheses and separators of the conflict target,
+            // the column names are collected by `push_identifier`
+            (state @ Upsert::Target { .. }, TARGET_START | SEPARATOR | TARGET_END) => {
+                *self = state;
+            }
+            // `sql()` or `on_constraint()` targets, which cannot be turned
+            // into the join condition of the merge statement
+            (Upsert::Target { .. }, _) => {
+                *self = Upsert::Unsupported {
+                    reason: "only columns are supported as conflict target",
+                };
+            }
+            (Upsert::Merge { insert }, RETURNING) => {
+                out.push_str(&insert);
+                out.push_str(sql);
+                *self = Upsert::Unsupported {
+                    reason: "upserts with a `RETURNING` clause require Oracle 23ai",
+                };
+            }
+            (Upsert::Merge { insert }, _) => {
+                out.push_str(sql);
+                *self = Upsert::Merge { insert };
+            }
+            (_, INSERT) => {
+                *self = Upsert::Insert { start: out.len() };
+                out.push_str(sql);
+            }
+            (state @ (Upsert::Insert { .. } | Upsert::Columns { .. }), _) => {
+                out.push_str(sql);
+                *self = state;
+            }
+            (_, _) => {
+                out.push_str(sql);
+            }
+        }
+    }
+
+    pub(super) fn push_identifier(&mut self, out: &mut String, identifier: &str) {
+        match self {
+            Upsert::Columns { statement } => {
+                out.push_str(identifier);
+                statement.columns.push(identifier.to_owned());
+            }
+            Upsert::Target { target, .. } => target.push(identifier.to_owned()),
+            _ => out.push_str(identifier),
+        }
+    }
+
+    /// Checks that the written upsert could be written as merge statement
+    pub(super) fn check(&self) -> QueryResult<()> {
+        match self {
+            Upsert::Unsupported { reason } => Err(Error::QueryBuilderError((*reason).into())),
+            _ => Ok(()),
+        }
+    }
+
+    pub(super) fn finish(&mut self, out: &mut String) {
+        if let Upsert::Merge { insert } = mem::take(self) {
+            out.push_str(&insert);
+        }
+    }
+}
+
+impl InsertStatement {
+    fn write_merge(&self, out: &mut String, target: &[String]) {
+        out.push_str("MERGE INTO ");
+        out.push_str(&self.table);
+        out.push_str(" USING (SELECT ");
+        let mut source = self
+            .columns
+            .iter()
+            .zip(&self.values)
+            .filter(|(_, value)| *value != DEFAULT)
+            .peekable();
+        if source.peek().is_none() {
+            out.push_str("NULL");
+        }
+        for (idx, (column, value)) in source.enumerate() {
+            if idx > 0 {
+                out.push_str(SEPARATOR);
+            }
+            out.push_str(value);
+            out.push(' ');
+            out.push_str(column);
+        }
+        out.push_str(" FROM DUAL) excluded ON (");
+        for (idx, column) in target.iter().enumerate() {
+            if idx > 0 {
+                out.push_str(" AND ");
+            }
+            out.push_str(&self.table);
+            out.push('.');
+            out.push_str(column);
+            out.push_str(" = excluded.");
+            out.push_str(column);
+        }
+        out.push(')');
+    }
+
+    fn merge_insert(&self) -> String {
+        let values = self
+            .columns
+            .iter()
+            .zip(&self.values)
+            .map(|(column, value)| {
+                if value == DEFAULT {
+                    DEFAULT.to_owned()
+                } else {
+                    format!("excluded.{column}")
+                }
+            })
+            .collect::<Vec<_>>();
+        format!(
+            " WHEN NOT MATCHED THEN INSERT ({}) VALUES ({})",
+            self.columns.join(SEPARATOR),
+            values.join(SEPARATOR)
+        )
+    }
+}
@@ -0,0 +1,128 @@
+use diesel::query_builder::{AsQuery, AstPass, Query, QueryFragment, QueryId};
+use diesel::query_dsl::RunQueryDsl;
+use diesel::result::QueryResult;
+
+use crate::oracle::backend::Oracle;
+use crate::oracle::connection::OciConnection;
+use crate::oracle::query_builder::OciQueryBuilder;
+
+/// Configure how the rows of a query are fetched
+///
+/// The settings overwrite the connection level defaults configured via
+/// [`OciConnection::set_prefetch_rows`] and [`OciConnection::set_fetch_array_size`]
+/// for a single query.
+///
+/// ```no_run
+/// # use diesel_oci::{OciConnection, OciFetchDsl, OciRowByRowLoadingMode};
+/// # use diesel::prelude::*;
+/// # table! {
+/// #    users {
+/// #        id -> Integer,
+/// #        name -> Text,
+/// #    }
+/// # }
+/// # fn run(conn: &mut OciConnection) -> QueryResult<()> {
+/// // a single round trip for a single row lookup
+/// let name = users::table
+///     .filter(users::id.eq(42))
+///     .select(users::name)
+///     .prefetch_rows(2)
+///     .get_result::<String>(conn)?;
+///
+/// // large batches for exporting a table
+/// let rows = users::table
+///     .fetch_array_size(10_000)
+///     .load_iter::<(i32, String), OciRowByRowLoadingMode>(conn)?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait OciFetchDsl: AsQuery + Sized {
+    /// Set the number of rows prefetched while executing this query
+    fn prefetch_rows(self, rows: u32) -> WithFetchOptions<Self::Query> {
+        WithFetchOptions {
+            query: self.as_query(),
+            options: FetchOptions {
+                prefetch_rows: Some(rows),
+                fetch_array_size: None,
+            },
+        }
+    }
+
+    /// Set the number of rows fetched per round trip while iterating over the results
+    fn fetch_array_size(self, rows: u32) -> WithFetchOptions<Self::Query> {
+        WithFetchOptions {
+            query: self.as_query(),
+            options: FetchOptions {
+                prefetch_rows: None,
+                fetch_array_size: Some(rows),
+            },
+        }
+    }
+}
+
+impl<T> OciFetchDsl for T where T: AsQuery + RunQueryDsl<OciConnection> {}
+
+/// Return type of [`OciFetchDsl::prefetch_rows`] and [`OciFetchDsl::fetch_array_size`]
+///
+/// The settings are written to the query builder together with the
+/// sql of the wrapped query and applied to the statement prepared for it.
+#[derive(Debug, Clone, Copy)]
+#[must_use = "Queries are only executed when calling `load`, `get_result` or similar."]
+pub struct WithFetchOptions<T> {
+    query: T,
+    options: FetchOptions,
+}
+
+impl<T> WithFetchOptions<T> {
+    /// Set the number of rows prefetched while executing this query
+    pub fn prefetch_rows(mut self, rows: u32) -> Self {
+        self.options.prefetch_rows = Some(rows);
+        self
+    }
+
+    /// Set the number of rows fetched per round trip while iterating over the results
+    pub fn fetch_array_size(mut self, rows: u32) -> Self {
+        self.options.fetch_array_size = Some(rows);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub(crate) struct FetchOptions {
+    pub(crate) prefetch_rows: Option<u32>,
+    pub(crate) fetch_array_size: Option<u32>,
+}
+
+impl<T> Query for WithFetchOptions<T>
+where
+    T: Query,
+{
+    type SqlType = T::SqlType;
+}
+
+impl<T> QueryId for WithFetchOptions<T>
+where
+    T: QueryId,
+{
+    type QueryId = WithFetchOptions<T::QueryId>;
+
+    // the options are no part of the type, so the type
+    // does not identify the prepared statement
+    const HAS_STATIC_QUERY_ID: bool = false;
+}
+
+impl<T> QueryFragment<Oracle> for WithFetchOptions<T>
+where
+    T: QueryFragment<Oracle>,
+{
+    fn walk_ast<'b>(&'b self, out: AstPass<'_, 'b, Oracle>) -> QueryResult<()> {
+        self.query.walk_ast(out)
+    }
+
+    fn to_sql(&self, out: &mut OciQueryBuilder, backend: &Oracle) -> QueryResult<()> {
+        out.fetch_options = self.options;
+        self.query.to_sql(out, backend)
+    }
+}
+
+impl<T> RunQueryDsl<OciConnection> for WithFetchOptions<T> {}
@@ -1 +1,5 @@
+mod fetch_options;
 mod save_changes_dsl;
+
+pub(crate) use self::fetch_options::FetchOptions;
+pub use self::fetch_options::{OciFetchDsl, WithFetchOptions};
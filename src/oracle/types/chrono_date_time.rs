@@ -1,15 +1,18 @@
 extern crate chrono_time as chrono;
-use chrono::NaiveTime;
-use diesel::deserialize::FromSql;
-use diesel::serialize::{IsNull, Output, ToSql};
+use chrono::{NaiveTime, Timelike};
+use diesel::deserialize::{self, FromSql};
+use diesel::serialize::{self, IsNull, Output, ToSql};
 use diesel::sql_types::*;
+use diesel_derives::{AsExpression, FromSqlRow};
+use oracle::sql_type::IntervalDS;
 use std::error::Error;
 
 use crate::oracle::backend::Oracle;
+use crate::oracle::types::SqlTimestamptz;
 
 use crate::oracle::connection::bind_collector::BindValue;
 
-use self::chrono::{NaiveDate, NaiveDateTime};
+use self::chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
 
 use super::super::connection::{InnerValue, OracleValue};
 
@@ -35,6 +38,86 @@ impl ToSql<Timestamp, Oracle> for NaiveDateTime {
     }
 }
 
+fn timestamptz_from_sql(bytes: OracleValue<'_>) -> deserialize::Result<DateTime<FixedOffset>> {
+    match bytes.inner {
+        InnerValue::Raw { raw_value, .. } => {
+            <DateTime<FixedOffset> as oracle::sql_type::FromSql>::from_sql(raw_value)
+                .map_err(Into::into)
+        }
+        InnerValue::TimestampTz(t) => Ok(t),
+        _ => Err("Invalid value for timestamp with time zone".into()),
+    }
+}
+
+impl FromSql<SqlTimestamptz, Oracle> for DateTime<FixedOffset> {
+    fn from_sql(bytes: OracleValue<'_>) -> deserialize::Result<Self> {
+        timestamptz_from_sql(bytes)
+    }
+}
+
+impl FromSql<SqlTimestamptz, Oracle> for DateTime<Utc> {
+    fn from_sql(bytes: OracleValue<'_>) -> deserialize::Result<Self> {
+        timestamptz_from_sql(bytes).map(|t| t.with_timezone(&Utc))
+    }
+}
+
+impl FromSql<SqlTimestamptz, Oracle> for DateTime<Local> {
+    fn from_sql(bytes: OracleValue<'_>) -> deserialize::Result<Self> {
+        timestamptz_from_sql(bytes).map(|t| t.with_timezone(&Local))
+    }
+}
+
+impl<Tz> ToSql<SqlTimestamptz, Oracle> for DateTime<Tz>
+where
+    Tz: TimeZone,
+    Tz::Offset: Sync + Send,
+    DateTime<Tz>: std::fmt::Debug,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Oracle>) -> serialize::Result {
+        out.set_value(BindValue::Borrowed(self));
+        Ok(IsNull::No)
+    }
+}
+
+/// Wrapper type to use `chrono::DateTime` values with [`SqlTimestamptz`]
+/// expressions in diesel's query DSL
+///
+/// Diesel only provides `AsExpression` implementations for `chrono::DateTime`
+/// for its own time zone aware sql types, so this wrapper is required to bind
+/// values to `TIMESTAMP WITH TIME ZONE` columns, e.g. via `.eq()`.
+/// Loading values does not require this wrapper, `DateTime<Utc>`,
+/// `DateTime<FixedOffset>` and `DateTime<Local>` can be loaded directly.
+#[derive(Debug, Clone, PartialEq, Eq, AsExpression, FromSqlRow)]
+#[diesel(sql_type = SqlTimestamptz)]
+pub struct OciTimestamptz<Tz: TimeZone>(pub DateTime<Tz>);
+
+impl<Tz: TimeZone> From<DateTime<Tz>> for OciTimestamptz<Tz> {
+    fn from(value: DateTime<Tz>) -> Self {
+        Self(value)
+    }
+}
+
+impl<Tz> ToSql<SqlTimestamptz, Oracle> for OciTimestamptz<Tz>
+where
+    Tz: TimeZone,
+    Tz::Offset: Sync + Send,
+    Self: std::fmt::Debug,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Oracle>) -> serialize::Result {
+        ToSql::<SqlTimestamptz, Oracle>::to_sql(&self.0, out)
+    }
+}
+
+impl<Tz> FromSql<SqlTimestamptz, Oracle> for OciTimestamptz<Tz>
+where
+    Tz: TimeZone,
+    DateTime<Tz>: FromSql<SqlTimestamptz, Oracle>,
+{
+    fn from_sql(bytes: OracleValue<'_>) -> deserialize::Result<Self> {
+        FromSql::<SqlTimestamptz, Oracle>::from_sql(bytes).map(Self)
+    }
+}
+
 impl FromSql<Date, Oracle> for NaiveDate {
     fn from_sql(bytes: OracleValue<'_>) -> Result<Self, Box<dyn Error + Send + Sync>> {
         match bytes.inner {
@@ -57,16 +140,76 @@ impl ToSql<Date, Oracle> for NaiveDate {
     }
 }
 
+// Oracle has no dedicated time of day type, therefore `Time` values
+// are represented as `INTERVAL DAY(0) TO SECOND` containing the
+// duration since midnight
+struct TimeWrapper(NaiveTime);
+
+impl oracle::sql_type::ToSql for TimeWrapper {
+    fn oratype(&self, _conn: &oracle::Connection) -> oracle::Result<oracle::sql_type::OracleType> {
+        Ok(oracle::sql_type::OracleType::IntervalDS(0, 9))
+    }
+
+    fn to_sql(&self, val: &mut oracle::SqlValue) -> oracle::Result<()> {
+        let interval = IntervalDS::new(
+            0,
+            self.0.hour() as i32,
+            self.0.minute() as i32,
+            self.0.second() as i32,
+            self.0.nanosecond() as i32,
+        )?;
+        val.set(&interval)
+    }
+}
+
+pub(crate) fn time_from_interval(interval: &IntervalDS) -> oracle::Result<NaiveTime> {
+    if interval.days() != 0 {
+        return Err(oracle::Error::new(
+            oracle::ErrorKind::OutOfRange,
+            format!("Interval {} does not represent a time of day", interval),
+        ));
+    }
+    u32::try_from(interval.hours())
+        .ok()
+        .zip(u32::try_from(interval.minutes()).ok())
+        .zip(u32::try_from(interval.seconds()).ok())
+        .zip(u32::try_from(interval.nanoseconds()).ok())
+        .and_then(|(((h, m), s), n)| NaiveTime::from_hms_nano_opt(h, m, s, n))
+        .ok_or_else(|| {
+            oracle::Error::new(
+                oracle::ErrorKind::OutOfRange,
+                format!("Interval {} does not represent a time of day", interval),
+            )
+        })
+}
+
 impl ToSql<Time, Oracle> for NaiveTime {
-    fn to_sql<'b>(&'b self, _out: &mut Output<'b, '_, Oracle>) -> diesel::serialize::Result {
-        unimplemented!("No time support in the oracle crate yet")
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Oracle>) -> diesel::serialize::Result {
+        out.set_value(BindValue::Owned(Box::new(TimeWrapper(*self))));
+        Ok(IsNull::No)
     }
 }
 
 impl FromSql<Time, Oracle> for NaiveTime {
-    fn from_sql(
-        _bytes: <Oracle as diesel::backend::Backend>::RawValue<'_>,
-    ) -> diesel::deserialize::Result<Self> {
-        unimplemented!("No time support in the oracle crate yet")
+    fn from_sql(bytes: OracleValue<'_>) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        match bytes.inner {
+            InnerValue::Raw {
+                raw_value,
+                tpe: oracle::sql_type::OracleType::IntervalDS(..),
+            } => {
+                let interval = <IntervalDS as oracle::sql_type::FromSql>::from_sql(raw_value)?;
+                time_from_interval(&interval).map_err(Into::into)
+            }
+            // also allow to load the time part of date or timestamp columns
+            InnerValue::Raw {
+                raw_value,
+                tpe: oracle::sql_type::OracleType::Date | oracle::sql_type::OracleType::Timestamp(_),
+            } => <NaiveDateTime as oracle::sql_type::FromSql>::from_sql(raw_value)
+                .map(|t| t.time())
+                .map_err(Into::into),
+            InnerValue::Time(t) => Ok(t),
+            InnerValue::Timestamp(t) => Ok(t.time()),
+            _ => Err("Invalid value for time".into()),
+        }
     }
 }
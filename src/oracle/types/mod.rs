@@ -53,9 +53,15 @@ pub enum OciDataType {
     /// A date value
     Date,
     /// A time value
+    ///
+    /// Oracle does not provide a time of day type, so these values are
+    /// stored as `INTERVAL DAY(0) TO SECOND` containing the time since midnight.
+    /// Loading the time part of `DATE` and `TIMESTAMP` columns is supported as well.
     Time,
     /// A timestamp value
     Timestamp,
+    /// A timestamp value with time zone information
+    TimestampTz,
     /// A time interval value with years and months
     IntervalYM,
     /// A time interval value with days, hours, minutes, seconds and possibly fractions of seconds
@@ -134,6 +140,32 @@ impl HasSqlType<Timestamp> for Oracle {
     }
 }
 
+/// Sql type that represents the Oracle `TIMESTAMP WITH TIME ZONE` and
+/// `TIMESTAMP WITH LOCAL TIME ZONE` types, to be used with things like the `table!` macro
+///
+/// With the `chrono` feature enabled this type can be used with
+/// `chrono::DateTime<Utc>`, `chrono::DateTime<FixedOffset>` and `chrono::DateTime<Local>`.
+/// Use `OciTimestamptz` to bind these values in diesel's query DSL.
+/// Values of `TIMESTAMP WITH LOCAL TIME ZONE` columns are returned in the session time zone.
+///
+/// https://docs.oracle.com/en/database/oracle/oracle-database/23/nlspg/datetime-data-types-and-time-zone-support.html
+#[derive(Debug, Clone, Copy, Default, diesel::query_builder::QueryId)]
+pub struct SqlTimestamptz;
+
+impl SqlType for SqlTimestamptz {
+    type IsNull = is_nullable::NotNull;
+}
+
+impl SingleValue for SqlTimestamptz {}
+
+impl HasSqlType<SqlTimestamptz> for Oracle {
+    fn metadata(_: &mut Self::MetadataLookup) -> Self::TypeMetadata {
+        OciTypeMetadata {
+            tpe: OciDataType::TimestampTz,
+        }
+    }
+}
+
 impl HasSqlType<Bool> for Oracle {
     fn metadata(_: &mut Self::MetadataLookup) -> Self::TypeMetadata {
         OciTypeMetadata {
@@ -189,5 +221,9 @@ mod dynamic_schema_impls {
 
 #[cfg(feature = "chrono-time")]
 mod chrono_date_time;
+#[cfg(feature = "chrono-time")]
+pub(crate) use self::chrono_date_time::time_from_interval;
+#[cfg(feature = "chrono-time")]
+pub use self::chrono_date_time::OciTimestamptz;
 
 pub use interval::{OciIntervalDS, OciIntervalYM, SqlIntervalDS, SqlIntervalYM};
@@ -166,7 +166,13 @@ impl ToSql<Binary, Oracle> for [u8] {
 
 impl FromSql<Bool, Oracle> for bool {
     fn from_sql(bytes: OracleValue<'_>) -> deserialize::Result<Self> {
-        FromSql::<SmallInt, Oracle>::from_sql(bytes).map(|v: i16| v != 0)
+        match bytes.inner {
+            InnerValue::Raw {
+                raw_value,
+                tpe: oracle::sql_type::OracleType::Boolean,
+            } => <Self as oracle::sql_type::FromSql>::from_sql(raw_value).map_err(Into::into),
+            _ => FromSql::<SmallInt, Oracle>::from_sql(bytes).map(|v: i16| v != 0),
+        }
     }
 }
 
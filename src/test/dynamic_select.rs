@@ -16,7 +16,7 @@ enum MyDynamicValue {
 
 impl FromSql<Any, Oracle> for MyDynamicValue {
     fn from_sql(value: OracleValue) -> Result<Self> {
-        match value.value_type() {
+        match value.try_value_type()? {
             OciDataType::Integer => {
                 <i32 as FromSql<Integer, Oracle>>::from_sql(value).map(MyDynamicValue::Integer)
             }